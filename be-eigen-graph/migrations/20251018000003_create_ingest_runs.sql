@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS ingest_runs (
+                                           run_id       BIGSERIAL PRIMARY KEY,
+                                           kind         TEXT        NOT NULL,
+                                           status       TEXT        NOT NULL,
+                                           started_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+                                           finished_at  TIMESTAMPTZ NULL,
+                                           pages        INT         NOT NULL DEFAULT 0,
+                                           rows         INT         NOT NULL DEFAULT 0,
+                                           errors       INT         NOT NULL DEFAULT 0,
+                                           last_error   TEXT        NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_ingest_runs_kind_status_finished
+    ON ingest_runs (kind, status, finished_at DESC);
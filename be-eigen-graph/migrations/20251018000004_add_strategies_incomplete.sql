@@ -0,0 +1,2 @@
+ALTER TABLE operators_snapshot
+    ADD COLUMN IF NOT EXISTS strategies_incomplete BOOLEAN NOT NULL DEFAULT FALSE;
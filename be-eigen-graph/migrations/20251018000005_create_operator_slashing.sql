@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS operator_slashing (
+                                                 operator_id       TEXT   NOT NULL,
+                                                 slashing_id       TEXT   NOT NULL,
+                                                 block_number      BIGINT NULL,
+                                                 block_timestamp   BIGINT NOT NULL,
+                                                 transaction_hash  TEXT   NULL,
+
+                                                 CONSTRAINT pk_operator_slashing PRIMARY KEY (operator_id, slashing_id),
+    CONSTRAINT fk_operator_slashing_operator
+    FOREIGN KEY (operator_id) REFERENCES operators_snapshot(operator_id)
+    ON DELETE CASCADE
+    );
+
+CREATE INDEX IF NOT EXISTS idx_operator_slashing_operator_ts
+    ON operator_slashing (operator_id, block_timestamp DESC);
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS avs (
+                                   avs_id                    TEXT PRIMARY KEY,
+                                   operator_count            INT     NOT NULL,
+                                   last_update_block_ts      BIGINT  NOT NULL,
+                                   registrations_incomplete  BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE TABLE IF NOT EXISTS operator_avs_registration (
+                                                         avs_id                TEXT   NOT NULL,
+                                                         operator_id           TEXT   NOT NULL,
+                                                         status                INT    NOT NULL,
+                                                         last_update_block_ts  BIGINT NOT NULL,
+
+                                                         CONSTRAINT pk_operator_avs_registration PRIMARY KEY (avs_id, operator_id),
+    CONSTRAINT fk_operator_avs_registration_avs
+    FOREIGN KEY (avs_id) REFERENCES avs(avs_id)
+    ON DELETE CASCADE
+    );
+
+CREATE INDEX IF NOT EXISTS idx_operator_avs_registration_operator
+    ON operator_avs_registration (operator_id);
@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS operator_history (
+                                                operator_id            TEXT             NOT NULL,
+                                                captured_at            BIGINT           NOT NULL,
+                                                avs_count              INT              NOT NULL,
+                                                strategy_count         INT              NOT NULL,
+                                                slashing_count         INT              NOT NULL,
+                                                tvl_total_atomic       TEXT             NOT NULL,
+                                                hhi                    DOUBLE PRECISION NOT NULL,
+                                                last_update_block_ts   BIGINT           NOT NULL,
+
+                                                CONSTRAINT pk_operator_history PRIMARY KEY (operator_id, captured_at)
+    ) PARTITION BY RANGE (captured_at);
+
+CREATE TABLE IF NOT EXISTS operator_strategy_history (
+                                                         operator_id     TEXT   NOT NULL,
+                                                         strategy_id     TEXT   NOT NULL,
+                                                         captured_at     BIGINT NOT NULL,
+                                                         token_id        TEXT   NOT NULL,
+                                                         token_symbol    TEXT   NOT NULL,
+                                                         token_decimals  INT    NOT NULL,
+                                                         total_shares    TEXT   NOT NULL,
+                                                         exchange_rate   TEXT   NOT NULL,
+                                                         tvl_atomic      TEXT   NOT NULL,
+
+                                                         CONSTRAINT pk_operator_strategy_history PRIMARY KEY (operator_id, strategy_id, captured_at)
+    ) PARTITION BY RANGE (captured_at);
@@ -0,0 +1,5 @@
+ALTER TABLE operator_history
+    ADD COLUMN IF NOT EXISTS block_number BIGINT;
+
+CREATE INDEX IF NOT EXISTS idx_operator_history_captured_at
+    ON operator_history (captured_at);
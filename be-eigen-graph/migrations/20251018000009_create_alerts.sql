@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS alert_state (
+                                           rule         TEXT    NOT NULL,
+                                           operator_id  TEXT    NOT NULL,
+                                           since        BIGINT  NOT NULL,
+
+                                           CONSTRAINT pk_alert_state PRIMARY KEY (rule, operator_id)
+);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+                                                  delivery_id      BIGSERIAL PRIMARY KEY,
+                                                  event_id         TEXT        NOT NULL,
+                                                  url              TEXT        NOT NULL,
+                                                  event_type       TEXT        NOT NULL,
+                                                  rule             TEXT        NOT NULL,
+                                                  operator_id      TEXT        NOT NULL,
+                                                  payload          JSONB       NOT NULL,
+                                                  status           TEXT        NOT NULL,
+                                                  attempts         INT         NOT NULL DEFAULT 0,
+                                                  response_status  INT         NULL,
+                                                  last_error       TEXT        NULL,
+                                                  created_at       TIMESTAMPTZ NOT NULL DEFAULT now(),
+                                                  finished_at      TIMESTAMPTZ NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_status_created
+    ON webhook_deliveries (status, created_at DESC);
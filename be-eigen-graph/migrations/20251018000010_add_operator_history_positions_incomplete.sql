@@ -0,0 +1,2 @@
+ALTER TABLE operator_history
+    ADD COLUMN IF NOT EXISTS positions_incomplete BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,6 @@
+ALTER TABLE webhook_deliveries
+    ADD COLUMN IF NOT EXISTS next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_pending
+    ON webhook_deliveries (next_attempt_at, delivery_id)
+    WHERE status = 'pending';
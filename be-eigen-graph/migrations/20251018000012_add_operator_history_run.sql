@@ -0,0 +1,16 @@
+ALTER TABLE operator_history
+    ADD COLUMN IF NOT EXISTS run_id BIGINT;
+
+-- each run samples every page at one captured_at, taken just after the run
+-- row is inserted, so earlier samples belong to the last run started by then
+UPDATE operator_history h
+   SET run_id = (SELECT r.run_id
+                   FROM ingest_runs r
+                  WHERE r.kind = 'operators'
+                    AND EXTRACT(EPOCH FROM r.started_at)::BIGINT <= h.captured_at
+                  ORDER BY r.started_at DESC
+                  LIMIT 1)
+ WHERE h.run_id IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_operator_history_run_id
+    ON operator_history (run_id);
@@ -0,0 +1,3 @@
+-- multi-token operators have no strategy HHI until every position is priced
+ALTER TABLE operator_history
+    ALTER COLUMN hhi DROP NOT NULL;
@@ -0,0 +1,6 @@
+-- the worker reads the oldest pending delivery of each URL
+DROP INDEX IF EXISTS idx_webhook_deliveries_pending;
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_pending_url
+    ON webhook_deliveries (url, delivery_id)
+    WHERE status = 'pending';
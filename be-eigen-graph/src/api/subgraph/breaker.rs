@@ -0,0 +1,132 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy)]
+enum BreakerState {
+    Closed {
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// One probe is in flight. If it never reports back (its future was
+    /// dropped), another probe is let through once `cooldown` has passed.
+    HalfOpen {
+        since: Instant,
+    },
+}
+
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown,
+            state: Mutex::new(BreakerState::Closed { failures: 0 }),
+        }
+    }
+
+    pub fn allow(&self) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|p| p.into_inner());
+        let now = Instant::now();
+        match *state {
+            BreakerState::Closed { .. } => true,
+            BreakerState::Open { until } if now >= until => {
+                *state = BreakerState::HalfOpen { since: now };
+                true
+            }
+            BreakerState::HalfOpen { since } if now >= since + self.cooldown => {
+                *state = BreakerState::HalfOpen { since: now };
+                true
+            }
+            BreakerState::Open { .. } | BreakerState::HalfOpen { .. } => false,
+        }
+    }
+
+    pub fn on_success(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|p| p.into_inner());
+        *state = BreakerState::Closed { failures: 0 };
+    }
+
+    pub fn on_failure(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|p| p.into_inner());
+        *state = match *state {
+            BreakerState::Closed { failures } if failures + 1 < self.threshold => {
+                BreakerState::Closed {
+                    failures: failures + 1,
+                }
+            }
+            _ => BreakerState::Open {
+                until: Instant::now() + self.cooldown,
+            },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_millis(30);
+
+    fn tripped() -> CircuitBreaker {
+        let breaker = CircuitBreaker::new(2, COOLDOWN);
+        breaker.on_failure();
+        breaker.on_failure();
+        breaker
+    }
+
+    #[test]
+    fn opens_after_threshold_failures() {
+        let breaker = CircuitBreaker::new(2, COOLDOWN);
+        breaker.on_failure();
+        assert!(breaker.allow());
+        breaker.on_failure();
+        assert!(!breaker.allow());
+    }
+
+    #[test]
+    fn success_resets_failure_count() {
+        let breaker = CircuitBreaker::new(2, COOLDOWN);
+        breaker.on_failure();
+        breaker.on_success();
+        breaker.on_failure();
+        assert!(breaker.allow());
+    }
+
+    #[test]
+    fn half_open_lets_one_probe_through() {
+        let breaker = tripped();
+        std::thread::sleep(COOLDOWN);
+        assert!(breaker.allow());
+        assert!(!breaker.allow());
+        breaker.on_success();
+        assert!(breaker.allow());
+    }
+
+    #[test]
+    fn failed_probe_reopens() {
+        let breaker = tripped();
+        std::thread::sleep(COOLDOWN);
+        assert!(breaker.allow());
+        breaker.on_failure();
+        assert!(!breaker.allow());
+    }
+
+    #[test]
+    fn lost_probe_is_replaced_after_cooldown() {
+        let breaker = tripped();
+        std::thread::sleep(COOLDOWN);
+        assert!(breaker.allow());
+        // the probe never reports back
+        assert!(!breaker.allow());
+        std::thread::sleep(COOLDOWN);
+        assert!(breaker.allow());
+    }
+}
@@ -1,17 +1,283 @@
-use reqwest::{Client, Url};
+use crate::api::subgraph::breaker::CircuitBreaker;
+use crate::api::subgraph::errors::InfraError;
+use crate::api::subgraph::tape::{SubgraphMode, Tape, TapeEntry};
+use crate::metrics::{error_inc, subgraph_observe};
+use crate::models::subgraph::{GraphQLRequest, GraphQLResponse};
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Client, StatusCode, Url};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug)]
+pub struct SubgraphOptions {
+    pub connect_timeout: Duration,
+    pub request_timeout: Duration,
+    pub max_retries: u32,
+    pub backoff_base: Duration,
+    pub backoff_max: Duration,
+    pub breaker_threshold: u32,
+    pub breaker_cooldown: Duration,
+    pub mode: SubgraphMode,
+    pub tape_dir: PathBuf,
+}
+
+impl Default for SubgraphOptions {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(3),
+            request_timeout: Duration::from_secs(15),
+            max_retries: 3,
+            backoff_base: Duration::from_millis(200),
+            backoff_max: Duration::from_secs(5),
+            breaker_threshold: 5,
+            breaker_cooldown: Duration::from_secs(30),
+            mode: SubgraphMode::Live,
+            tape_dir: PathBuf::from("tapes"),
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct SubgraphClient {
     pub http: Client,
     pub endpoint: Url,
+    options: SubgraphOptions,
+    breaker: Arc<CircuitBreaker>,
+    tape: Tape,
 }
 
 impl SubgraphClient {
     pub fn new(endpoint: Url) -> Self {
+        Self::with_options(endpoint, SubgraphOptions::default())
+    }
+
+    pub fn with_options(endpoint: Url, options: SubgraphOptions) -> Self {
         let http = Client::builder()
             .user_agent("operators-snapshot/0.1")
+            .connect_timeout(options.connect_timeout)
+            .timeout(options.request_timeout)
             .build()
             .expect("subgraph client");
-        Self { http, endpoint }
+        let breaker = Arc::new(CircuitBreaker::new(
+            options.breaker_threshold,
+            options.breaker_cooldown,
+        ));
+        let tape = Tape::new(options.tape_dir.clone());
+        Self {
+            http,
+            endpoint,
+            options,
+            breaker,
+            tape,
+        }
+    }
+
+    pub async fn query<V, T>(&self, query: &str, vars: &V) -> Result<T, InfraError>
+    where
+        V: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        if self.options.mode == SubgraphMode::Replay {
+            return self.replay(query, vars).await;
+        }
+
+        let start = Instant::now();
+        if !self.breaker.allow() {
+            subgraph_observe("circuit_open", start.elapsed());
+            return Err(InfraError::CircuitOpen);
+        }
+
+        let mut attempt = 0;
+        let res = loop {
+            let res = self.post_once::<V, T>(query, vars).await;
+            let Err(e) = &res else {
+                break res;
+            };
+            if !is_retryable(e) || attempt >= self.options.max_retries {
+                break res;
+            }
+            let delay = match e {
+                InfraError::RateLimited(Some(after)) => *after,
+                _ => self.backoff(attempt),
+            };
+            // a server asking for a longer pause than we would ever back off
+            // fails the request rather than holding it open
+            if delay > self.options.backoff_max {
+                break res;
+            }
+            subgraph_observe("retry", start.elapsed());
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        };
+
+        // one outcome per logical request, however many attempts it took
+        if res.as_ref().is_err_and(is_retryable) {
+            self.breaker.on_failure();
+        } else {
+            self.breaker.on_success();
+        }
+        subgraph_observe(result_label(&res), start.elapsed());
+        res
+    }
+
+    async fn post_once<V, T>(&self, query: &str, vars: &V) -> Result<T, InfraError>
+    where
+        V: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let body = GraphQLRequest {
+            query,
+            variables: Some(vars),
+        };
+        let resp = self
+            .http
+            .post(self.endpoint.clone())
+            .json(&body)
+            .send()
+            .await?;
+        if resp.status() == StatusCode::TOO_MANY_REQUESTS {
+            return Err(InfraError::RateLimited(retry_after(resp.headers())));
+        }
+        let raw = resp.error_for_status()?.json::<Value>().await?;
+        if self.options.mode == SubgraphMode::Record {
+            self.record(query, vars, &raw).await;
+        }
+        decode_response(raw)
+    }
+
+    async fn replay<V, T>(&self, query: &str, vars: &V) -> Result<T, InfraError>
+    where
+        V: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let start = Instant::now();
+        let variables = serde_json::to_value(vars)?;
+        let key = Tape::key(query, &variables);
+        let res = match self.tape.read(&key).await {
+            Ok(Some(entry)) => decode_response(entry.response),
+            Ok(None) => Err(InfraError::TapeMiss(key)),
+            Err(e) => Err(InfraError::Tape(e)),
+        };
+        subgraph_observe(result_label(&res), start.elapsed());
+        res
+    }
+
+    async fn record<V>(&self, query: &str, vars: &V, raw: &Value)
+    where
+        V: Serialize + ?Sized,
+    {
+        let Ok(variables) = serde_json::to_value(vars) else {
+            error_inc("subgraph_tape_write");
+            return;
+        };
+        let key = Tape::key(query, &variables);
+        let entry = TapeEntry {
+            query: query.to_string(),
+            variables,
+            response: raw.clone(),
+        };
+        if let Err(_e) = self.tape.write(&key, &entry).await {
+            error_inc("subgraph_tape_write");
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self
+            .options
+            .backoff_base
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.options.backoff_max);
+        let jitter_ms = rand::rng().random_range(0..=exp.as_millis() as u64 / 2);
+        exp / 2 + Duration::from_millis(jitter_ms)
+    }
+}
+
+fn decode_response<T: DeserializeOwned>(raw: Value) -> Result<T, InfraError> {
+    let resp: GraphQLResponse<T> = serde_json::from_value(raw)?;
+    if let Some(errs) = resp.errors {
+        return Err(InfraError::GraphQL(errs));
+    }
+    resp.data.ok_or(InfraError::EmptyData)
+}
+
+/// `Retry-After` as either delay-seconds or an HTTP date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let secs = (at.timestamp() - chrono::Utc::now().timestamp()).max(0);
+    Some(Duration::from_secs(secs as u64))
+}
+
+fn is_retryable(e: &InfraError) -> bool {
+    match e {
+        InfraError::Http(e) => match e.status() {
+            Some(status) => status.is_server_error(),
+            None => e.is_timeout() || e.is_connect() || e.is_request() || e.is_body(),
+        },
+        InfraError::RateLimited(_) => true,
+        _ => false,
+    }
+}
+
+fn result_label<T>(res: &Result<T, InfraError>) -> &'static str {
+    match res {
+        Ok(_) => "ok",
+        Err(InfraError::Http(e)) if e.is_timeout() => "timeout",
+        Err(InfraError::Http(e)) if e.is_decode() => "decode_error",
+        Err(InfraError::Http(e)) if e.status().is_some() => "http_status",
+        Err(InfraError::Http(_)) => "transport",
+        Err(InfraError::Decode(_)) => "decode_error",
+        Err(InfraError::GraphQL(_)) => "graphql_error",
+        Err(InfraError::EmptyData) => "empty_data",
+        Err(InfraError::RateLimited(_)) => "rate_limited",
+        Err(InfraError::CircuitOpen) => "circuit_open",
+        Err(InfraError::TapeMiss(_)) => "tape_miss",
+        Err(InfraError::Tape(_)) => "tape_io",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(value: &str) -> HeaderMap {
+        let mut h = HeaderMap::new();
+        h.insert(RETRY_AFTER, HeaderValue::from_str(value).unwrap());
+        h
+    }
+
+    #[test]
+    fn retry_after_seconds() {
+        assert_eq!(retry_after(&headers("7")), Some(Duration::from_secs(7)));
+    }
+
+    #[test]
+    fn retry_after_http_date_in_the_past_is_zero() {
+        assert_eq!(
+            retry_after(&headers("Wed, 21 Oct 2015 07:28:00 GMT")),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn retry_after_missing_or_garbage() {
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+        assert_eq!(retry_after(&headers("soon")), None);
+    }
+
+    #[test]
+    fn rate_limits_are_retryable() {
+        assert!(is_retryable(&InfraError::RateLimited(None)));
+        assert!(!is_retryable(&InfraError::EmptyData));
+        assert!(!is_retryable(&InfraError::CircuitOpen));
     }
 }
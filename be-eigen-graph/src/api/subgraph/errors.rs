@@ -1,3 +1,4 @@
+use std::time::Duration;
 use thiserror::Error;
 
 use crate::models::subgraph::GraphQLError;
@@ -5,7 +6,7 @@ use crate::models::subgraph::GraphQLError;
 #[derive(Debug, Error)]
 pub enum InfraError {
     #[error("http error: {0}")]
-    Http(#[from] reqwest::Error),
+    Http(reqwest::Error),
 
     #[error("decode error: {0}")]
     Decode(#[from] serde_json::Error),
@@ -15,4 +16,42 @@ pub enum InfraError {
 
     #[error("empty graphql data payload")]
     EmptyData,
+
+    #[error("subgraph rate limited the request")]
+    RateLimited(Option<Duration>),
+
+    #[error("subgraph circuit breaker is open")]
+    CircuitOpen,
+
+    #[error("no recorded subgraph response for key {0}")]
+    TapeMiss(String),
+
+    #[error("subgraph tape error: {0}")]
+    Tape(std::io::Error),
+}
+
+/// reqwest includes the request URL in its errors, and `SUBGRAPH_URL` embeds
+/// the hosted API key, so it is dropped before the error goes anywhere.
+impl From<reqwest::Error> for InfraError {
+    fn from(e: reqwest::Error) -> Self {
+        InfraError::Http(e.without_url())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn http_errors_do_not_leak_the_url() {
+        let err = reqwest::Client::new()
+            .get("http://127.0.0.1:1/api/secret-key")
+            .send()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("secret-key"));
+
+        let err = InfraError::from(err);
+        assert!(!err.to_string().contains("secret-key"));
+    }
 }
@@ -1,3 +1,5 @@
+pub mod breaker;
 pub mod client;
 pub mod errors;
 pub mod queries;
+pub mod tape;
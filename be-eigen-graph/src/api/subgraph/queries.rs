@@ -1 +1,8 @@
 pub const OPERATORS_SNAPSHOT: &str = include_str!("queries/operators_snapshot.graphql");
+pub const OPERATORS_SNAPSHOT_CURSOR: &str =
+    include_str!("queries/operators_snapshot_cursor.graphql");
+pub const OPERATOR_STRATEGIES: &str = include_str!("queries/operator_strategies.graphql");
+pub const OPERATOR_DETAIL: &str = include_str!("queries/operator_detail.graphql");
+pub const OPERATOR_SLASHINGS: &str = include_str!("queries/operator_slashings.graphql");
+pub const AVS_PAGE: &str = include_str!("queries/avs_page.graphql");
+pub const AVS_OPERATORS: &str = include_str!("queries/avs_operators.graphql");
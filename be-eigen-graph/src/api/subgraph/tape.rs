@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum SubgraphMode {
+    #[default]
+    Live,
+    Record,
+    Replay,
+}
+
+impl FromStr for SubgraphMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "live" => Ok(SubgraphMode::Live),
+            "record" => Ok(SubgraphMode::Record),
+            "replay" => Ok(SubgraphMode::Replay),
+            other => Err(format!("unknown subgraph mode: {other}")),
+        }
+    }
+}
+
+/// One captured GraphQL exchange. The request is kept alongside the raw
+/// response so tapes stay readable and can be edited by hand.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TapeEntry {
+    pub query: String,
+    pub variables: Value,
+    pub response: Value,
+}
+
+/// A directory of recorded request/response pairs, one JSON file per
+/// distinct query + variables.
+#[derive(Clone, Debug)]
+pub struct Tape {
+    dir: PathBuf,
+}
+
+impl Tape {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn key(query: &str, variables: &Value) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(normalize_query(query).as_bytes());
+        hasher.update(b"\n");
+        hasher.update(variables.to_string().as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    pub async fn read(&self, key: &str) -> std::io::Result<Option<TapeEntry>> {
+        let bytes = match tokio::fs::read(self.path_for(key)).await {
+            Ok(b) => b,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        Ok(Some(serde_json::from_slice(&bytes)?))
+    }
+
+    pub async fn write(&self, key: &str, entry: &TapeEntry) -> std::io::Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let bytes = serde_json::to_vec_pretty(entry)?;
+        tokio::fs::write(self.path_for(key), bytes).await
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+}
+
+// Queries are embedded with `include_str!`, so whitespace differences between
+// a recorded tape and the current file should not cause a miss.
+fn normalize_query(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -1,7 +1,16 @@
-use crate::api::subgraph::client::SubgraphClient;
+use crate::api::subgraph::client::{SubgraphClient, SubgraphOptions};
+use crate::caching::backend::{CacheBackend, LayeredCache};
+use crate::caching::memory::MemoryCache;
+use crate::caching::redis::RedisCache;
 use crate::config::AppConfig;
 use crate::metrics;
+use crate::repositories::ingest_runs::fail_abandoned_ingest_runs;
 use crate::routes::v1;
+use crate::services::alerts::webhook_sender::{WebhookOptions, WebhookSender};
+use crate::services::ingest::operators_ingest::spawn_operators_ingest;
+use crate::services::live::live_hub::LiveHub;
+use crate::services::live::live_publish::seed_live_aggregates;
+use crate::services::prices::price_feed::PriceFeed;
 use crate::state::AppState;
 use axum::Router;
 use axum::http::Method;
@@ -9,8 +18,8 @@ use axum::routing::get;
 use redis::Client;
 use redis::aio::ConnectionManager;
 use sqlx::postgres::PgPoolOptions;
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 use tower_http::cors::Any;
 use tower_http::cors::CorsLayer;
@@ -34,14 +43,92 @@ pub async fn app() -> Router {
         Err(_) => None,
     };
 
+    let mut tiers: Vec<Arc<dyn CacheBackend>> = Vec::new();
+    if config.memory_cache_capacity > 0 {
+        tiers.push(Arc::new(MemoryCache::new(
+            config.memory_cache_capacity,
+            config.memory_cache_ttl_seconds,
+        )));
+    }
+    if let Some(conn) = redis {
+        tiers.push(Arc::new(RedisCache::new(conn)));
+    }
+    let cache = Arc::new(LayeredCache::new(
+        tiers,
+        config.memory_cache_ttl_seconds,
+        &format!("eigen-graph:{}", config.app_env),
+    ));
+
+    let prices_db = config.timescale_database_url.as_deref().and_then(|url| {
+        PgPoolOptions::new()
+            .max_connections(5)
+            .acquire_timeout(Duration::from_secs(5))
+            .connect_lazy(url)
+            .ok()
+    });
+    let prices = PriceFeed::new(prices_db, PriceFeed::parse_products(&config.price_products));
+
+    let subgraph_options = SubgraphOptions {
+        connect_timeout: Duration::from_millis(config.subgraph_connect_timeout_ms),
+        request_timeout: Duration::from_millis(config.subgraph_request_timeout_ms),
+        max_retries: config.subgraph_max_retries,
+        backoff_base: Duration::from_millis(config.subgraph_backoff_base_ms),
+        backoff_max: Duration::from_millis(config.subgraph_backoff_max_ms),
+        breaker_threshold: config.subgraph_breaker_threshold,
+        breaker_cooldown: Duration::from_secs(config.subgraph_breaker_cooldown_seconds),
+        mode: config.subgraph_mode.clone(),
+        tape_dir: PathBuf::from(&config.subgraph_tape_dir),
+    };
+
+    let webhooks = WebhookSender::new(WebhookOptions {
+        urls: config.webhook_urls.clone(),
+        secret: config.webhook_secret.clone(),
+        timeout: Duration::from_millis(config.webhook_timeout_ms),
+        max_attempts: config.webhook_max_attempts.max(1),
+        backoff_base: Duration::from_millis(config.webhook_backoff_base_ms),
+        backoff_max: Duration::from_millis(config.webhook_backoff_max_ms),
+    });
+
     let state = AppState {
-        subgraph_client: SubgraphClient::new(config.subgraph_url),
-        operators_snapshot: Arc::new(Mutex::new(HashMap::new())),
+        subgraph_client: SubgraphClient::with_options(
+            config.subgraph_url.clone(),
+            subgraph_options,
+        ),
         db,
-        redis,
+        cache,
         redis_ttl_seconds: config.redis_ttl_seconds,
+        redis_stale_seconds: config.redis_stale_seconds,
+        prices,
+        admin_token: config.admin_token.clone(),
+        fixture_path: config.fixture_snapshot_path.map(PathBuf::from),
+        webhooks,
+        alert_hhi_threshold: config.alert_hhi_threshold,
+        alert_recent_window_seconds: config.alert_recent_window_seconds,
+        live: LiveHub::new(),
     };
 
+    state.webhooks.spawn_worker(state.db.clone());
+
+    let seed = state.clone();
+    tokio::spawn(async move {
+        if seed_live_aggregates(&seed).await.is_err() {
+            metrics::error_inc("live_seed");
+        }
+    });
+
+    if config.ingest_enabled {
+        // this process is the only writer of runs, so any still running are
+        // left over from before it started
+        if fail_abandoned_ingest_runs(&state.db).await.is_err() {
+            metrics::error_inc("ingest_abandoned_runs");
+        }
+        spawn_operators_ingest(
+            state.clone(),
+            Duration::from_secs(config.ingest_interval_seconds.max(1)),
+            config.ingest_page_size.clamp(1, 1000),
+        );
+    }
+
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods([
@@ -0,0 +1,597 @@
+//! A small stand-in for the EigenLayer subgraph. It answers the operator and
+//! AVS queries in `src/api/subgraph/queries` from a generated or JSON-seeded
+//! dataset, with optional latency and error injection. AVSs are derived from
+//! the operators: each one is registered with `avsCount` of them.
+//!
+//! Environment:
+//! - `MOCK_PORT` (default 8003)
+//! - `MOCK_SEED_PATH`: an `OperatorsSnapshotData` JSON file, e.g.
+//!   `docs/responses-examples/operators-snapshot.json`; when unset a dataset
+//!   is generated from `MOCK_OPERATORS` (default 500) and `MOCK_RNG_SEED`
+//! - `MOCK_LATENCY_MS` / `MOCK_LATENCY_JITTER_MS`: per-request delay
+//! - `MOCK_HTTP_ERROR_RATE` / `MOCK_GRAPHQL_ERROR_RATE`: probability in 0..=1
+//!   of answering with a 503 or with a GraphQL `errors` payload
+//!
+//! The dataset has no history: a `block` argument is validated against the
+//! mock head and echoed in `_meta`, but every block returns the same data.
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::post;
+use axum::{Router, serve};
+use be_eigen_graph::models::operators_snapshot::{
+    OperatorDto, OperatorSlashingDto, OperatorStrategyLinkDto, OperatorsSnapshotData,
+    StrategyLiteDto, TokenDto,
+};
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::cmp::Ordering;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+const MAX_FIRST: i64 = 1000;
+const MAX_SKIP: i64 = 5000;
+const NESTED_STRATEGIES: usize = 100;
+const HEAD_BLOCK: i64 = 21_500_000;
+const BLOCK_TIME_SECS: i64 = 12;
+const AVS_COUNT: usize = 40;
+
+const TOKENS: &[(&str, i32)] = &[
+    ("stETH", 18),
+    ("rETH", 18),
+    ("cbETH", 18),
+    ("wBETH", 18),
+    ("ETHx", 18),
+    ("EIGEN", 18),
+    ("bEIGEN", 18),
+    ("USDC", 6),
+    ("WBTC", 8),
+];
+
+#[derive(Clone, Debug)]
+struct MockConfig {
+    port: u16,
+    seed_path: Option<String>,
+    operators: usize,
+    rng_seed: u64,
+    latency_ms: u64,
+    latency_jitter_ms: u64,
+    http_error_rate: f64,
+    graphql_error_rate: f64,
+}
+
+impl MockConfig {
+    fn from_env() -> Self {
+        dotenvy::dotenv().ok();
+        Self {
+            port: env_or("MOCK_PORT", 8003),
+            seed_path: env::var("MOCK_SEED_PATH").ok(),
+            operators: env_or("MOCK_OPERATORS", 500),
+            rng_seed: env_or("MOCK_RNG_SEED", 42),
+            latency_ms: env_or("MOCK_LATENCY_MS", 0),
+            latency_jitter_ms: env_or("MOCK_LATENCY_JITTER_MS", 0),
+            http_error_rate: env_or("MOCK_HTTP_ERROR_RATE", 0.0f64).clamp(0.0, 1.0),
+            graphql_error_rate: env_or("MOCK_GRAPHQL_ERROR_RATE", 0.0f64).clamp(0.0, 1.0),
+        }
+    }
+}
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .and_then(|s| s.parse::<T>().ok())
+        .unwrap_or(default)
+}
+
+#[derive(Clone)]
+struct MockState {
+    config: Arc<MockConfig>,
+    operators: Arc<Vec<OperatorDto>>,
+    avss: Arc<Vec<MockAvs>>,
+}
+
+struct MockAvs {
+    id: String,
+    last_update: i64,
+    /// Ordered by operator id.
+    registrations: Vec<MockRegistration>,
+}
+
+struct MockRegistration {
+    operator_id: String,
+    status: i32,
+    last_update: i64,
+}
+
+#[derive(Deserialize)]
+struct GraphQLBody {
+    query: String,
+    #[serde(default)]
+    variables: Value,
+}
+
+#[tokio::main]
+async fn main() {
+    let config = MockConfig::from_env();
+    let operators = match config.seed_path.as_deref() {
+        Some(path) => load_seed(path),
+        None => generate(config.operators, config.rng_seed),
+    };
+    let avss = generate_avss(&operators, config.rng_seed);
+    println!(
+        "mock subgraph: {} operators and {} AVSs on port {}",
+        operators.len(),
+        avss.len(),
+        config.port
+    );
+
+    let port = config.port;
+    let state = MockState {
+        config: Arc::new(config),
+        operators: Arc::new(operators),
+        avss: Arc::new(avss),
+    };
+    let app = Router::new()
+        .route("/", post(graphql))
+        .route("/{*path}", post(graphql))
+        .with_state(state);
+
+    let listener = TcpListener::bind(("0.0.0.0", port))
+        .await
+        .expect("Cannot bind mock subgraph port");
+    serve(listener, app).await.expect("Cannot serve");
+}
+
+async fn graphql(State(state): State<MockState>, Json(body): Json<GraphQLBody>) -> Response {
+    let config = &state.config;
+    let (delay, http_error, graphql_error) = {
+        let mut rng = rand::rng();
+        let jitter = match config.latency_jitter_ms {
+            0 => 0,
+            j => rng.random_range(0..=j),
+        };
+        (
+            config.latency_ms + jitter,
+            rng.random_bool(config.http_error_rate),
+            rng.random_bool(config.graphql_error_rate),
+        )
+    };
+    if delay > 0 {
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+    }
+    if http_error {
+        return (StatusCode::SERVICE_UNAVAILABLE, "injected failure").into_response();
+    }
+    if graphql_error {
+        return gql_error("injected failure");
+    }
+
+    let vars = &body.variables;
+    let ops = state.operators.as_slice();
+    let result = match operation_name(&body.query) {
+        Some("OperatorsSnapshot") => operators_snapshot(ops, vars),
+        Some("OperatorsSnapshotCursor") => operators_snapshot_cursor(ops, vars),
+        Some("OperatorStrategies") => operator_strategies(ops, vars),
+        Some("OperatorDetail") => operator_detail(ops, vars),
+        Some("OperatorSlashings") => operator_slashings(ops, vars),
+        Some("AvsPage") => avs_page(&state.avss, vars),
+        Some("AvsOperators") => avs_operators(&state.avss, vars),
+        Some(other) => Err(format!("unsupported operation: {other}")),
+        None => Err("missing operation name".to_string()),
+    };
+
+    match result {
+        Ok(data) => Json(json!({ "data": data })).into_response(),
+        Err(msg) => gql_error(&msg),
+    }
+}
+
+fn gql_error(message: &str) -> Response {
+    Json(json!({ "data": null, "errors": [{ "message": message }] })).into_response()
+}
+
+fn operation_name(query: &str) -> Option<&str> {
+    let rest = query.trim_start().strip_prefix("query")?.trim_start();
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    (end > 0).then(|| &rest[..end])
+}
+
+/* --- operations --- */
+
+fn operators_snapshot(ops: &[OperatorDto], vars: &Value) -> Result<Value, String> {
+    let (first, skip) = first_skip(vars)?;
+    let has_slashing = int_var(vars, "hasSlashing").unwrap_or(0);
+    let order_by = str_var(vars, "orderBy").unwrap_or("id");
+    let desc = str_var(vars, "orderDirection") == Some("desc");
+
+    let mut matched: Vec<&OperatorDto> = ops
+        .iter()
+        .filter(|o| i64::from(o.slashing_count) >= has_slashing)
+        .collect();
+    let key: fn(&OperatorDto) -> i64 = match order_by {
+        "id" => |_| 0,
+        "avsCount" => |o| o.avs_count.into(),
+        "strategyCount" => |o| o.strategy_count.into(),
+        "slashingCount" => |o| o.slashing_count.into(),
+        "lastUpdateBlockTimestamp" => |o| o.last_update_block_timestamp.parse().unwrap_or(0),
+        other => return Err(format!("unsupported orderBy: {other}")),
+    };
+    matched.sort_by(|a, b| {
+        let o = key(a).cmp(&key(b)).then_with(|| a.id.cmp(&b.id));
+        if desc { o.reverse() } else { o }
+    });
+
+    Ok(json!({
+        "_meta": meta_json(vars)?,
+        "operators": page(&matched, first, skip)
+            .iter()
+            .map(|o| operator_json(o, 1, 0, false))
+            .collect::<Vec<_>>()
+    }))
+}
+
+fn operators_snapshot_cursor(ops: &[OperatorDto], vars: &Value) -> Result<Value, String> {
+    let first = bounded(int_var(vars, "first").unwrap_or(100), MAX_FIRST, "first")?;
+    let cursor = str_var(vars, "cursor").unwrap_or("");
+    let has_slashing = int_var(vars, "hasSlashing").unwrap_or(0);
+
+    let mut matched: Vec<&OperatorDto> = ops
+        .iter()
+        .filter(|o| o.id.as_str() > cursor && i64::from(o.slashing_count) >= has_slashing)
+        .collect();
+    matched.sort_by(|a, b| a.id.cmp(&b.id));
+
+    Ok(json!({
+        "_meta": meta_json(vars)?,
+        "operators": page(&matched, first, 0)
+            .iter()
+            .map(|o| operator_json(o, 1, 0, false))
+            .collect::<Vec<_>>()
+    }))
+}
+
+fn operator_strategies(ops: &[OperatorDto], vars: &Value) -> Result<Value, String> {
+    let first = bounded(int_var(vars, "first").unwrap_or(100), MAX_FIRST, "first")?;
+    let cursor = str_var(vars, "cursor").unwrap_or("");
+    let operator = find_operator(ops, vars).map(|o| {
+        // link ids are `{operator}-{strategy}`
+        let mut links: Vec<(String, &OperatorStrategyLinkDto)> = o
+            .strategies
+            .iter()
+            .map(|l| (format!("{}-{}", o.id, l.strategy.id), l))
+            .filter(|(id, _)| id.as_str() > cursor)
+            .collect();
+        links.sort_by(|a, b| a.0.cmp(&b.0));
+        json!({
+            "strategies": page(&links, first, 0)
+                .iter()
+                .map(|(id, l)| {
+                    let mut link = strategy_link_json(l);
+                    link["id"] = json!(id);
+                    link
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+    Ok(json!({ "operator": operator }))
+}
+
+fn operator_detail(ops: &[OperatorDto], vars: &Value) -> Result<Value, String> {
+    let (first, skip) = first_skip(vars)?;
+    let operator = find_operator(ops, vars).map(|o| operator_json(o, first, skip, true));
+    Ok(json!({ "operator": operator }))
+}
+
+/// Resumes strictly after `(beforeTs, afterId)` in the newest-first order.
+fn operator_slashings(ops: &[OperatorDto], vars: &Value) -> Result<Value, String> {
+    let first = bounded(int_var(vars, "first").unwrap_or(100), MAX_FIRST, "first")?;
+    let before_ts = str_var(vars, "beforeTs")
+        .and_then(|ts| ts.parse::<i64>().ok())
+        .ok_or("beforeTs must be a BigInt")?;
+    let after_id = str_var(vars, "afterId").unwrap_or("");
+    let operator = find_operator(ops, vars).map(|o| {
+        let rest: Vec<OperatorSlashingDto> = sorted_slashings(o)
+            .into_iter()
+            .filter(|s| {
+                let ts = s.block_timestamp.parse::<i64>().unwrap_or(0);
+                ts < before_ts || (ts == before_ts && s.id.as_deref().unwrap_or("") > after_id)
+            })
+            .collect();
+        json!({
+            "slashings": page(&rest, first, 0)
+                .iter()
+                .map(|s| slashing_json(s, true))
+                .collect::<Vec<_>>()
+        })
+    });
+    Ok(json!({ "operator": operator }))
+}
+
+fn avs_page(avss: &[MockAvs], vars: &Value) -> Result<Value, String> {
+    let first = bounded(int_var(vars, "first").unwrap_or(100), MAX_FIRST, "first")?;
+    let cursor = str_var(vars, "cursor").unwrap_or("");
+
+    let matched: Vec<&MockAvs> = avss.iter().filter(|a| a.id.as_str() > cursor).collect();
+    Ok(json!({
+        "avss": page(&matched, first, 0)
+            .iter()
+            .map(|a| avs_json(a, MAX_FIRST, ""))
+            .collect::<Vec<_>>()
+    }))
+}
+
+fn avs_operators(avss: &[MockAvs], vars: &Value) -> Result<Value, String> {
+    let first = bounded(int_var(vars, "first").unwrap_or(100), MAX_FIRST, "first")?;
+    let cursor = str_var(vars, "cursor").unwrap_or("");
+    let id = str_var(vars, "id").map(str::to_lowercase);
+    let avs = avss
+        .iter()
+        .find(|a| Some(&a.id) == id.as_ref())
+        .map(|a| avs_json(a, first, cursor));
+    Ok(json!({ "avs": avs }))
+}
+
+/* --- projection --- */
+
+/// Registrations are ordered by operator id and start after `reg_cursor`.
+fn avs_json(a: &MockAvs, reg_first: i64, reg_cursor: &str) -> Value {
+    let registrations: Vec<&MockRegistration> = a
+        .registrations
+        .iter()
+        .filter(|r| r.operator_id.as_str() > reg_cursor)
+        .collect();
+    json!({
+        "id": a.id,
+        "operatorCount": a.registrations.iter().filter(|r| r.status == 1).count(),
+        "lastUpdateBlockTimestamp": a.last_update.to_string(),
+        "registrations": page(&registrations, reg_first, 0)
+            .iter()
+            .map(|r| json!({
+                "status": r.status,
+                "lastUpdateBlockTimestamp": r.last_update.to_string(),
+                "operator": { "id": r.operator_id },
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn operator_json(o: &OperatorDto, slash_first: i64, slash_skip: i64, full: bool) -> Value {
+    json!({
+        "id": o.id,
+        "avsCount": o.avs_count,
+        "strategyCount": o.strategy_count,
+        "slashingCount": o.slashing_count,
+        "lastUpdateBlockTimestamp": o.last_update_block_timestamp,
+        "slashings": page(&sorted_slashings(o), slash_first, slash_skip)
+            .iter()
+            .map(|s| slashing_json(s, full))
+            .collect::<Vec<_>>(),
+        "strategies": o.strategies
+            .iter()
+            .take(NESTED_STRATEGIES)
+            .map(strategy_link_json)
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn slashing_json(s: &OperatorSlashingDto, full: bool) -> Value {
+    if full {
+        json!({
+            "id": s.id,
+            "blockNumber": s.block_number,
+            "blockTimestamp": s.block_timestamp,
+            "transactionHash": s.transaction_hash,
+        })
+    } else {
+        json!({ "id": s.id, "blockTimestamp": s.block_timestamp })
+    }
+}
+
+fn strategy_link_json(l: &OperatorStrategyLinkDto) -> Value {
+    json!({
+        "totalShares": l.total_shares,
+        "strategy": {
+            "id": l.strategy.id,
+            "exchangeRate": l.strategy.exchange_rate,
+            "token": l.strategy.token.as_ref().map(|t| json!({
+                "id": t.id,
+                "symbol": t.symbol,
+                "decimals": t.decimals,
+            })),
+        },
+    })
+}
+
+fn sorted_slashings(o: &OperatorDto) -> Vec<OperatorSlashingDto> {
+    let mut s = o.slashings.clone();
+    s.sort_by(|a, b| {
+        let at = a.block_timestamp.parse::<i64>().unwrap_or(0);
+        let bt = b.block_timestamp.parse::<i64>().unwrap_or(0);
+        match bt.cmp(&at) {
+            Ordering::Equal => a.id.cmp(&b.id),
+            o => o,
+        }
+    });
+    s
+}
+
+fn meta_json(vars: &Value) -> Result<Value, String> {
+    let number = match vars.pointer("/block/number").and_then(Value::as_i64) {
+        Some(n) if n > HEAD_BLOCK => {
+            return Err(format!(
+                "block number {n} is not yet indexed; latest is {HEAD_BLOCK}"
+            ));
+        }
+        Some(n) => n,
+        None => HEAD_BLOCK,
+    };
+    let timestamp = chrono::Utc::now().timestamp() - (HEAD_BLOCK - number) * BLOCK_TIME_SECS;
+    Ok(json!({ "block": { "number": number, "timestamp": timestamp } }))
+}
+
+/* --- variables --- */
+
+fn first_skip(vars: &Value) -> Result<(i64, i64), String> {
+    let first = bounded(int_var(vars, "first").unwrap_or(100), MAX_FIRST, "first")?;
+    let skip = bounded(int_var(vars, "skip").unwrap_or(0), MAX_SKIP, "skip")?;
+    Ok((first, skip))
+}
+
+fn bounded(v: i64, max: i64, name: &str) -> Result<i64, String> {
+    if (0..=max).contains(&v) {
+        Ok(v)
+    } else {
+        Err(format!(
+            "The `{name}` argument must be between 0 and {max}, but is {v}"
+        ))
+    }
+}
+
+fn int_var(vars: &Value, key: &str) -> Option<i64> {
+    vars.get(key).and_then(Value::as_i64)
+}
+
+fn str_var<'a>(vars: &'a Value, key: &str) -> Option<&'a str> {
+    vars.get(key).and_then(Value::as_str)
+}
+
+fn find_operator<'a>(ops: &'a [OperatorDto], vars: &Value) -> Option<&'a OperatorDto> {
+    let id = str_var(vars, "id")?.to_lowercase();
+    ops.iter().find(|o| o.id == id)
+}
+
+fn page<T>(items: &[T], first: i64, skip: i64) -> &[T] {
+    let start = (skip as usize).min(items.len());
+    let end = start.saturating_add(first as usize).min(items.len());
+    &items[start..end]
+}
+
+/* --- dataset --- */
+
+fn load_seed(path: &str) -> Vec<OperatorDto> {
+    let bytes = std::fs::read(path).expect("Cannot read MOCK_SEED_PATH");
+    let data: OperatorsSnapshotData =
+        serde_json::from_slice(&bytes).expect("MOCK_SEED_PATH is not an operators snapshot");
+    data.operators
+}
+
+fn generate(count: usize, seed: u64) -> Vec<OperatorDto> {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let strategies: Vec<StrategyLiteDto> = (0..150)
+        .map(|i| {
+            let (symbol, decimals) = TOKENS[i % TOKENS.len()];
+            let rate: u128 = 10u128.pow(18) + rng.random_range(0..10u128.pow(17));
+            StrategyLiteDto {
+                id: random_address(&mut rng),
+                exchange_rate: rate.to_string(),
+                token: Some(TokenDto {
+                    id: format!("0x{:040x}", i % TOKENS.len() + 1),
+                    symbol: symbol.to_string(),
+                    decimals,
+                }),
+            }
+        })
+        .collect();
+
+    (0..count)
+        .map(|_| {
+            // a few operators exceed the nested page sizes so the client's
+            // follow-up pagination gets exercised
+            let strategy_count = if rng.random_bool(0.01) {
+                rng.random_range(101..=strategies.len())
+            } else {
+                rng.random_range(0..=8)
+            };
+            let offset = rng.random_range(0..strategies.len());
+            let links = (0..strategy_count)
+                .map(|k| {
+                    let shares = match rng.random_range(0..10) {
+                        0 => 0u128,
+                        _ => rng.random_range(1..10_000u128) * 10u128.pow(16),
+                    };
+                    OperatorStrategyLinkDto {
+                        total_shares: shares.to_string(),
+                        strategy: strategies[(offset + k) % strategies.len()].clone(),
+                    }
+                })
+                .collect();
+
+            let slashing_count = match rng.random_range(0..100) {
+                0 => rng.random_range(1000..=1500),
+                1..=5 => rng.random_range(1..=5),
+                _ => 0,
+            };
+            let last_update = rng.random_range(1_700_000_000i64..1_760_000_000);
+            let slashings = (0..slashing_count)
+                .map(|k| OperatorSlashingDto {
+                    id: Some(format!("{}-{k}", random_address(&mut rng))),
+                    block_number: Some((20_000_000 + k).to_string()),
+                    block_timestamp: (last_update - k * 60).to_string(),
+                    transaction_hash: Some(format!("0x{:064x}", rng.random::<u128>())),
+                })
+                .collect();
+
+            OperatorDto {
+                id: random_address(&mut rng),
+                avs_count: rng.random_range(0..=20),
+                strategy_count: strategy_count as i32,
+                slashing_count: slashing_count as i32,
+                last_update_block_timestamp: last_update.to_string(),
+                slashings,
+                strategies: links,
+                strategies_incomplete: false,
+                slashings_incomplete: false,
+            }
+        })
+        .collect()
+}
+
+/// Registers every operator with `avsCount` distinct AVSs; about one in ten
+/// also has a past registration it has since left.
+fn generate_avss(ops: &[OperatorDto], seed: u64) -> Vec<MockAvs> {
+    let mut rng = StdRng::seed_from_u64(seed.wrapping_add(1));
+    let mut avss: Vec<MockAvs> = (0..AVS_COUNT)
+        .map(|_| MockAvs {
+            id: random_address(&mut rng),
+            last_update: 0,
+            registrations: Vec::new(),
+        })
+        .collect();
+
+    for op in ops {
+        let active = (op.avs_count.max(0) as usize).min(AVS_COUNT);
+        let left = usize::from(active < AVS_COUNT && rng.random_bool(0.1));
+        let last_update = op.last_update_block_timestamp.parse().unwrap_or(0);
+        for (k, i) in sample(&mut rng, AVS_COUNT, active + left)
+            .into_iter()
+            .enumerate()
+        {
+            avss[i].registrations.push(MockRegistration {
+                operator_id: op.id.clone(),
+                status: i32::from(k < active),
+                last_update,
+            });
+            avss[i].last_update = avss[i].last_update.max(last_update);
+        }
+    }
+
+    for avs in avss.iter_mut() {
+        avs.registrations
+            .sort_by(|a, b| a.operator_id.cmp(&b.operator_id));
+    }
+    avss.sort_by(|a, b| a.id.cmp(&b.id));
+    avss
+}
+
+fn random_address(rng: &mut StdRng) -> String {
+    format!("0x{:08x}{:032x}", rng.random::<u32>(), rng.random::<u128>())
+}
@@ -0,0 +1,107 @@
+//! A local endpoint for exercising alert webhooks. It checks the
+//! `X-Eigen-Graph-Signature` header of every POST and prints the event.
+//!
+//! Environment:
+//! - `WEBHOOK_RECEIVER_PORT` (default 8002)
+//! - `WEBHOOK_SECRET`: must match the backend's secret
+//! - `WEBHOOK_RECEIVER_FAIL_RATE`: probability in 0..=1 of answering with a
+//!   503, to exercise the sender's retries
+//! - `WEBHOOK_RECEIVER_TOLERANCE_SECONDS` (default 300): maximum signature age
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::{Router, serve};
+use be_eigen_graph::services::alerts::webhook_sender::{
+    DELIVERY_HEADER, EVENT_HEADER, SIGNATURE_HEADER, verify_signature,
+};
+use rand::Rng;
+use std::env;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+#[derive(Clone, Debug)]
+struct ReceiverConfig {
+    port: u16,
+    secret: String,
+    fail_rate: f64,
+    tolerance_secs: i64,
+}
+
+impl ReceiverConfig {
+    fn from_env() -> Self {
+        dotenvy::dotenv().ok();
+        Self {
+            port: env_or("WEBHOOK_RECEIVER_PORT", 8002),
+            secret: env::var("WEBHOOK_SECRET").expect("WEBHOOK_SECRET must be set"),
+            fail_rate: env_or("WEBHOOK_RECEIVER_FAIL_RATE", 0.0f64).clamp(0.0, 1.0),
+            tolerance_secs: env_or("WEBHOOK_RECEIVER_TOLERANCE_SECONDS", 300),
+        }
+    }
+}
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .and_then(|s| s.parse::<T>().ok())
+        .unwrap_or(default)
+}
+
+#[tokio::main]
+async fn main() {
+    let config = ReceiverConfig::from_env();
+    println!("webhook receiver on port {}", config.port);
+
+    let port = config.port;
+    let app = Router::new()
+        .route("/", post(receive))
+        .route("/{*path}", post(receive))
+        .with_state(Arc::new(config));
+
+    let listener = TcpListener::bind(("0.0.0.0", port))
+        .await
+        .expect("Cannot bind webhook receiver port");
+    serve(listener, app).await.expect("Cannot serve");
+}
+
+async fn receive(
+    State(config): State<Arc<ReceiverConfig>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+    };
+    let body = String::from_utf8_lossy(&body);
+
+    let now_ts = chrono::Utc::now().timestamp();
+    if !verify_signature(
+        &config.secret,
+        header(SIGNATURE_HEADER),
+        &body,
+        now_ts,
+        config.tolerance_secs,
+    ) {
+        println!(
+            "rejected delivery {}: bad signature",
+            header(DELIVERY_HEADER)
+        );
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    if rand::rng().random_bool(config.fail_rate) {
+        println!("failing delivery {} on purpose", header(DELIVERY_HEADER));
+        return StatusCode::SERVICE_UNAVAILABLE;
+    }
+
+    println!(
+        "{} {}: {body}",
+        header(EVENT_HEADER),
+        header(DELIVERY_HEADER)
+    );
+    StatusCode::NO_CONTENT
+}
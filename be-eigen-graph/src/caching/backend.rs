@@ -0,0 +1,269 @@
+use crate::metrics::cache_inc;
+use crate::models::cached::DataSource;
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+/// Entries are fresh for `fresh_secs`; for a further `stale_secs` they are
+/// still served while a single background refresh runs, then they expire.
+#[derive(Clone, Copy, Debug)]
+pub struct CacheTtl {
+    pub fresh_secs: u64,
+    pub stale_secs: u64,
+}
+
+impl CacheTtl {
+    pub fn expire_secs(&self) -> u64 {
+        self.fresh_secs.saturating_add(self.stale_secs).max(1)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEnvelope<T> {
+    pub cached_at: i64,
+    pub data: T,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CacheTier {
+    Memory,
+    Redis,
+    Noop,
+}
+
+impl CacheTier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheTier::Memory => "memory",
+            CacheTier::Redis => "redis",
+            CacheTier::Noop => "noop",
+        }
+    }
+
+    pub fn source(&self) -> DataSource {
+        match self {
+            CacheTier::Memory => DataSource::Memory,
+            CacheTier::Redis | CacheTier::Noop => DataSource::Redis,
+        }
+    }
+}
+
+/// Bump whenever a cached DTO changes shape so old entries are never read.
+pub const CACHE_SCHEMA_VERSION: u32 = 3;
+
+/// A byte-oriented cache tier. Implementations swallow their own transport
+/// errors: a failing tier behaves like a miss.
+pub trait CacheBackend: Send + Sync {
+    fn tier(&self) -> CacheTier;
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<Vec<u8>>>;
+    fn set<'a>(&'a self, key: &'a str, value: Vec<u8>, ttl_secs: u64) -> BoxFuture<'a, ()>;
+    /// Records `key` as a member of `tag`; `tagged` lists the members.
+    fn tag<'a>(&'a self, tag: &'a str, key: &'a str, ttl_secs: u64) -> BoxFuture<'a, ()>;
+    fn tagged<'a>(&'a self, tag: &'a str) -> BoxFuture<'a, Vec<String>>;
+    fn delete<'a>(&'a self, keys: &'a [String]) -> BoxFuture<'a, usize>;
+    fn purge_prefix<'a>(&'a self, prefix: &'a str) -> BoxFuture<'a, usize>;
+}
+
+pub struct NoopCache;
+
+impl CacheBackend for NoopCache {
+    fn tier(&self) -> CacheTier {
+        CacheTier::Noop
+    }
+
+    fn get<'a>(&'a self, _key: &'a str) -> BoxFuture<'a, Option<Vec<u8>>> {
+        async { None }.boxed()
+    }
+
+    fn set<'a>(&'a self, _key: &'a str, _value: Vec<u8>, _ttl_secs: u64) -> BoxFuture<'a, ()> {
+        async {}.boxed()
+    }
+
+    fn tag<'a>(&'a self, _tag: &'a str, _key: &'a str, _ttl_secs: u64) -> BoxFuture<'a, ()> {
+        async {}.boxed()
+    }
+
+    fn purge_prefix<'a>(&'a self, _prefix: &'a str) -> BoxFuture<'a, usize> {
+        async { 0 }.boxed()
+    }
+
+    fn tagged<'a>(&'a self, _tag: &'a str) -> BoxFuture<'a, Vec<String>> {
+        async { Vec::new() }.boxed()
+    }
+
+    fn delete<'a>(&'a self, _keys: &'a [String]) -> BoxFuture<'a, usize> {
+        async { 0 }.boxed()
+    }
+}
+
+/// Tiers are consulted in order (e.g. memory → Redis). A hit in a lower tier
+/// is copied into the tiers above it for `backfill_ttl_secs`.
+///
+/// Every key is stored under `<namespace>:v<CACHE_SCHEMA_VERSION>:`, so
+/// callers work with bare keys and environments sharing a Redis never collide.
+/// Tag sets live under `<namespace>:tag:v<CACHE_SCHEMA_VERSION>:`, outside
+/// the data keys, so purges only ever count cached values.
+pub struct LayeredCache {
+    tiers: Vec<Arc<dyn CacheBackend>>,
+    backfill_ttl_secs: u64,
+    prefix: String,
+    tag_prefix: String,
+}
+
+impl LayeredCache {
+    pub fn new(tiers: Vec<Arc<dyn CacheBackend>>, backfill_ttl_secs: u64, namespace: &str) -> Self {
+        let tiers = if tiers.is_empty() {
+            vec![Arc::new(NoopCache) as Arc<dyn CacheBackend>]
+        } else {
+            tiers
+        };
+        Self {
+            tiers,
+            backfill_ttl_secs,
+            prefix: format!("{namespace}:v{CACHE_SCHEMA_VERSION}:"),
+            tag_prefix: format!("{namespace}:tag:v{CACHE_SCHEMA_VERSION}:"),
+        }
+    }
+
+    pub async fn get_bytes(&self, key: &str) -> Option<(Vec<u8>, CacheTier)> {
+        self.lookup(key, |b| Some(b.to_vec())).await
+    }
+
+    pub async fn set_bytes(&self, key: &str, value: Vec<u8>, ttl_secs: u64) {
+        let key = self.namespaced(key);
+        for tier in &self.tiers {
+            tier.set(&key, value.clone(), ttl_secs).await;
+        }
+    }
+
+    /// An entry that no longer decodes is reported as `decode_err` and
+    /// treated as a miss in that tier.
+    pub async fn get_json<T: DeserializeOwned>(&self, key: &str) -> Option<(T, CacheTier)> {
+        self.lookup(key, |b| serde_json::from_slice(b).ok()).await
+    }
+
+    pub async fn set_json<T: Serialize>(&self, key: &str, value: &T, ttl_secs: u64) {
+        match serde_json::to_vec(value) {
+            Ok(buf) => self.set_bytes(key, buf, ttl_secs).await,
+            Err(_) => cache_inc("set", "err"),
+        }
+    }
+
+    /// Stores `value` and tags the key with each operator id it contains, so
+    /// `purge_operator` can drop it after that operator is re-ingested.
+    pub async fn set_json_for_operators<T: Serialize>(
+        &self,
+        key: &str,
+        value: &T,
+        ttl_secs: u64,
+        operator_ids: &[String],
+    ) {
+        self.set_json(key, value, ttl_secs).await;
+        let key = self.namespaced(key);
+        for id in operator_ids {
+            let tag = self.operator_tag(id);
+            for tier in &self.tiers {
+                tier.tag(&tag, &key, ttl_secs).await;
+            }
+        }
+    }
+
+    pub async fn purge_prefix(&self, prefix: &str) -> usize {
+        let prefix = self.namespaced(prefix);
+        let mut purged = 0;
+        for tier in &self.tiers {
+            purged += tier.purge_prefix(&prefix).await;
+        }
+        purged
+    }
+
+    /// Tiers may know different members of a tag (a backfilled memory entry
+    /// is never tagged), so the union is deleted from every tier.
+    pub async fn purge_operator(&self, operator_id: &str) -> usize {
+        let tag = self.operator_tag(operator_id);
+        let mut keys: BTreeSet<String> = BTreeSet::new();
+        for tier in &self.tiers {
+            keys.extend(tier.tagged(&tag).await);
+        }
+        let keys: Vec<String> = keys.into_iter().collect();
+
+        let mut purged = 0;
+        for tier in &self.tiers {
+            purged += tier.delete(&keys).await;
+            tier.delete(std::slice::from_ref(&tag)).await;
+        }
+        purged
+    }
+
+    async fn lookup<T>(
+        &self,
+        key: &str,
+        decode: impl Fn(&[u8]) -> Option<T>,
+    ) -> Option<(T, CacheTier)> {
+        let key = self.namespaced(key);
+        for (i, tier) in self.tiers.iter().enumerate() {
+            let kind = tier.tier();
+            if kind == CacheTier::Noop {
+                continue;
+            }
+            let Some(bytes) = tier.get(&key).await else {
+                cache_inc(kind.as_str(), "miss");
+                continue;
+            };
+            let Some(value) = decode(&bytes) else {
+                cache_inc(kind.as_str(), "decode_err");
+                continue;
+            };
+            cache_inc(kind.as_str(), "hit");
+            for upper in &self.tiers[..i] {
+                upper.set(&key, bytes.clone(), self.backfill_ttl_secs).await;
+            }
+            return Some((value, kind));
+        }
+        None
+    }
+
+    fn namespaced(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+
+    fn operator_tag(&self, operator_id: &str) -> String {
+        format!("{}operator={}", self.tag_prefix, operator_id.to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::caching::memory::MemoryCache;
+
+    fn layered() -> LayeredCache {
+        LayeredCache::new(vec![Arc::new(MemoryCache::new(16, 60))], 60, "test")
+    }
+
+    #[tokio::test]
+    async fn operator_purge_counts_only_cached_values() {
+        let cache = layered();
+        let ids = vec!["0xAB".to_string()];
+        cache.set_json_for_operators("a", &1, 60, &ids).await;
+        cache.set_json_for_operators("b", &2, 60, &ids).await;
+        cache.set_json("c", &3, 60).await;
+
+        assert_eq!(cache.purge_operator("0xab").await, 2);
+        assert!(cache.get_json::<i32>("c").await.is_some());
+        assert_eq!(cache.purge_operator("0xab").await, 0);
+    }
+
+    #[tokio::test]
+    async fn prefix_purge_skips_tag_sets() {
+        let cache = layered();
+        let ids = vec!["0xab".to_string()];
+        cache.set_json_for_operators("a", &1, 60, &ids).await;
+
+        assert_eq!(cache.purge_prefix("").await, 1);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::caching::backend::{CacheBackend, CacheTier};
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use lru::LruCache;
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+struct Entry {
+    value: Vec<u8>,
+    expires_at: Instant,
+    /// Tags listing this key, so dropping the entry also unlists it.
+    tags: HashSet<String>,
+}
+
+struct LruState {
+    entries: LruCache<String, Entry>,
+    tags: HashMap<String, HashSet<String>>,
+}
+
+impl LruState {
+    fn unlist(&mut self, key: &str, entry: &Entry) {
+        for tag in &entry.tags {
+            if let Some(keys) = self.tags.get_mut(tag) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.tags.remove(tag);
+                }
+            }
+        }
+    }
+
+    fn drop_entry(&mut self, key: &str) -> bool {
+        match self.entries.pop(key) {
+            Some(entry) => {
+                self.unlist(key, &entry);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// In-process LRU tier. Entries live for at most `max_ttl_secs` regardless of
+/// the TTL requested, so a process never serves data much older than Redis
+/// would.
+pub struct MemoryCache {
+    state: Mutex<LruState>,
+    max_ttl_secs: u64,
+}
+
+impl MemoryCache {
+    pub fn new(capacity: usize, max_ttl_secs: u64) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            state: Mutex::new(LruState {
+                entries: LruCache::new(capacity),
+                tags: HashMap::new(),
+            }),
+            max_ttl_secs,
+        }
+    }
+
+    fn lookup(&self, key: &str) -> Option<Vec<u8>> {
+        let mut state = self.state.lock().unwrap_or_else(|p| p.into_inner());
+        let entry = state.entries.get(key)?;
+        if entry.expires_at > Instant::now() {
+            return Some(entry.value.clone());
+        }
+        state.drop_entry(key);
+        None
+    }
+
+    fn insert(&self, key: &str, value: Vec<u8>, ttl_secs: u64) {
+        let ttl = ttl_secs.min(self.max_ttl_secs);
+        if ttl == 0 {
+            return;
+        }
+        let mut state = self.state.lock().unwrap_or_else(|p| p.into_inner());
+        // a rewrite stays listed under the tags it already had
+        let tags = state.entries.pop(key).map(|e| e.tags).unwrap_or_default();
+        let entry = Entry {
+            value,
+            expires_at: Instant::now() + Duration::from_secs(ttl),
+            tags,
+        };
+        if let Some((evicted, old)) = state.entries.push(key.to_string(), entry) {
+            state.unlist(&evicted, &old);
+        }
+    }
+
+    fn add_tag(&self, tag: &str, key: &str) {
+        let mut state = self.state.lock().unwrap_or_else(|p| p.into_inner());
+        let Some(entry) = state.entries.peek_mut(key) else {
+            return;
+        };
+        entry.tags.insert(tag.to_string());
+        state
+            .tags
+            .entry(tag.to_string())
+            .or_default()
+            .insert(key.to_string());
+    }
+
+    fn remove_prefix(&self, prefix: &str) -> usize {
+        let mut state = self.state.lock().unwrap_or_else(|p| p.into_inner());
+        let keys: Vec<String> = state
+            .entries
+            .iter()
+            .map(|(k, _)| k)
+            .filter(|k| k.starts_with(prefix))
+            .cloned()
+            .collect();
+        for key in &keys {
+            state.drop_entry(key);
+        }
+        keys.len()
+    }
+
+    fn tagged_keys(&self, tag: &str) -> Vec<String> {
+        let state = self.state.lock().unwrap_or_else(|p| p.into_inner());
+        state
+            .tags
+            .get(tag)
+            .map(|keys| keys.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Counts the cached values removed; a tag set named in `keys` is
+    /// dropped too but not counted.
+    fn remove(&self, keys: &[String]) -> usize {
+        let mut state = self.state.lock().unwrap_or_else(|p| p.into_inner());
+        keys.iter()
+            .filter(|k| {
+                state.tags.remove(k.as_str());
+                state.drop_entry(k)
+            })
+            .count()
+    }
+}
+
+impl CacheBackend for MemoryCache {
+    fn tier(&self) -> CacheTier {
+        CacheTier::Memory
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<Vec<u8>>> {
+        let hit = self.lookup(key);
+        async move { hit }.boxed()
+    }
+
+    fn set<'a>(&'a self, key: &'a str, value: Vec<u8>, ttl_secs: u64) -> BoxFuture<'a, ()> {
+        self.insert(key, value, ttl_secs);
+        async {}.boxed()
+    }
+
+    fn tag<'a>(&'a self, tag: &'a str, key: &'a str, _ttl_secs: u64) -> BoxFuture<'a, ()> {
+        self.add_tag(tag, key);
+        async {}.boxed()
+    }
+
+    fn purge_prefix<'a>(&'a self, prefix: &'a str) -> BoxFuture<'a, usize> {
+        let purged = self.remove_prefix(prefix);
+        async move { purged }.boxed()
+    }
+
+    fn tagged<'a>(&'a self, tag: &'a str) -> BoxFuture<'a, Vec<String>> {
+        let keys = self.tagged_keys(tag);
+        async move { keys }.boxed()
+    }
+
+    fn delete<'a>(&'a self, keys: &'a [String]) -> BoxFuture<'a, usize> {
+        let purged = self.remove(keys);
+        async move { purged }.boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_the_least_recently_used_entry() {
+        let cache = MemoryCache::new(2, 60);
+        cache.insert("a", b"1".to_vec(), 60);
+        cache.insert("b", b"2".to_vec(), 60);
+        assert!(cache.lookup("a").is_some());
+
+        cache.insert("c", b"3".to_vec(), 60);
+
+        assert!(cache.lookup("b").is_none());
+        assert!(cache.lookup("a").is_some());
+        assert!(cache.lookup("c").is_some());
+    }
+
+    #[test]
+    fn eviction_unlists_the_key_from_its_tags() {
+        let cache = MemoryCache::new(1, 60);
+        cache.insert("a", b"1".to_vec(), 60);
+        cache.add_tag("t", "a");
+        assert_eq!(cache.tagged_keys("t"), vec!["a".to_string()]);
+
+        cache.insert("b", b"2".to_vec(), 60);
+
+        assert!(cache.tagged_keys("t").is_empty());
+    }
+}
@@ -1 +1,4 @@
+pub mod backend;
+pub mod memory;
 pub mod redis;
+pub mod single_flight;
@@ -1,9 +1,16 @@
+use crate::caching::backend::{CacheBackend, CacheTier};
 use crate::metrics::cache_inc;
-use crate::models::operators_snapshot::{OperatorOrderBy, OperatorsSnapshotVars, OrderDirection};
+use crate::models::operators_aggr::AggregatorParams;
+use crate::models::operators_snapshot::{
+    AsOf, BlockHeight, OperatorOrderBy, OperatorsSnapshotCursorVars, OperatorsSnapshotVars,
+    OrderDirection,
+};
+use futures::FutureExt;
+use futures::future::BoxFuture;
 use redis::AsyncCommands;
 use redis::aio::ConnectionManager;
-use serde::Serialize;
-use serde::de::DeserializeOwned;
+
+const SCAN_COUNT: usize = 500;
 
 pub fn key_snapshot(vars: &OperatorsSnapshotVars) -> String {
     let ob = match vars.order_by {
@@ -18,46 +25,180 @@ pub fn key_snapshot(vars: &OperatorsSnapshotVars) -> String {
         OrderDirection::Desc => "desc",
     };
     format!(
-        "snapshot:first={}:skip={}:orderBy={}:orderDir={}:hasSlashing={}",
-        vars.first, vars.skip, ob, od, vars.has_slashing
+        "snapshot:first={}:skip={}:orderBy={}:orderDir={}:hasSlashing={}:block={}",
+        vars.first,
+        vars.skip,
+        ob,
+        od,
+        vars.has_slashing,
+        block_part(vars.block)
+    )
+}
+
+pub fn key_snapshot_cursor(vars: &OperatorsSnapshotCursorVars) -> String {
+    format!(
+        "snapshot_cursor:first={}:cursor={}:hasSlashing={}:block={}",
+        vars.first,
+        vars.cursor,
+        vars.has_slashing,
+        block_part(vars.block)
     )
 }
 
+fn block_part(block: Option<BlockHeight>) -> String {
+    block.map_or_else(|| "head".to_string(), |b| b.number.to_string())
+}
+
 pub fn key_snapshot_page(first: i32, skip: i32) -> String {
     format!("snapshot_page:first={first}:skip={skip}")
 }
 
-pub async fn get_json<T: DeserializeOwned>(conn: &mut ConnectionManager, key: &str) -> Option<T> {
-    let res: Result<Option<Vec<u8>>, _> = conn.get(key).await;
-    match res {
-        Ok(Some(b)) => {
-            let v = serde_json::from_slice(&b).ok();
-            cache_inc("get", "hit");
-            v
+pub fn key_aggregates(
+    source: &str,
+    first: i32,
+    skip: i32,
+    after: Option<&str>,
+    as_of: Option<AsOf>,
+    params: &AggregatorParams,
+) -> String {
+    let as_of = match as_of {
+        Some(AsOf::Block(b)) => format!("block:{b}"),
+        Some(AsOf::Timestamp(ts)) => format!("ts:{ts}"),
+        None => "head".to_string(),
+    };
+    format!(
+        "aggregates:source={}:first={}:skip={}:after={}:asOf={}:topN={}:hhi={}:minTvl={}:operator={}",
+        source,
+        first,
+        skip,
+        after.unwrap_or(""),
+        as_of,
+        params.top_n,
+        params.hhi_threshold,
+        params.min_tvl_atomic.as_deref().unwrap_or(""),
+        params.focus_operator_id.as_deref().unwrap_or(""),
+    )
+}
+
+pub struct RedisCache {
+    conn: ConnectionManager,
+}
+
+impl RedisCache {
+    pub fn new(conn: ConnectionManager) -> Self {
+        Self { conn }
+    }
+}
+
+impl CacheBackend for RedisCache {
+    fn tier(&self) -> CacheTier {
+        CacheTier::Redis
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<Vec<u8>>> {
+        async move {
+            let mut conn = self.conn.clone();
+            let res: Result<Option<Vec<u8>>, _> = conn.get(key).await;
+            res.unwrap_or_else(|_| {
+                cache_inc("redis", "err");
+                None
+            })
+        }
+        .boxed()
+    }
+
+    fn set<'a>(&'a self, key: &'a str, value: Vec<u8>, ttl_secs: u64) -> BoxFuture<'a, ()> {
+        async move {
+            let mut conn = self.conn.clone();
+            let res: Result<(), _> = conn.set_ex(key, value, ttl_secs.max(1)).await;
+            match res {
+                Ok(_) => cache_inc("set", "ok"),
+                Err(_) => cache_inc("set", "err"),
+            }
+        }
+        .boxed()
+    }
+
+    fn tag<'a>(&'a self, tag: &'a str, key: &'a str, ttl_secs: u64) -> BoxFuture<'a, ()> {
+        async move {
+            let mut conn = self.conn.clone();
+            let res: Result<(), _> = redis::pipe()
+                .sadd(tag, key)
+                .ignore()
+                .expire(tag, ttl_secs.max(1) as i64)
+                .ignore()
+                .query_async(&mut conn)
+                .await;
+            if res.is_err() {
+                cache_inc("redis", "err");
+            }
         }
-        Ok(None) => {
-            cache_inc("get", "miss");
-            None
+        .boxed()
+    }
+
+    fn tagged<'a>(&'a self, tag: &'a str) -> BoxFuture<'a, Vec<String>> {
+        async move {
+            let mut conn = self.conn.clone();
+            conn.smembers(tag).await.unwrap_or_else(|_| {
+                cache_inc("redis", "err");
+                Vec::new()
+            })
+        }
+        .boxed()
+    }
+
+    fn delete<'a>(&'a self, keys: &'a [String]) -> BoxFuture<'a, usize> {
+        async move {
+            if keys.is_empty() {
+                return 0;
+            }
+            let mut conn = self.conn.clone();
+            conn.del(keys).await.unwrap_or_else(|_| {
+                cache_inc("redis", "err");
+                0
+            })
         }
-        Err(_) => {
-            cache_inc("get", "err");
-            None
+        .boxed()
+    }
+
+    fn purge_prefix<'a>(&'a self, prefix: &'a str) -> BoxFuture<'a, usize> {
+        async move {
+            let mut conn = self.conn.clone();
+            let pattern = format!("{}*", escape_glob(prefix));
+            let mut cursor: u64 = 0;
+            let mut purged = 0;
+            loop {
+                let res: Result<(u64, Vec<String>), _> = redis::cmd("SCAN")
+                    .arg(cursor)
+                    .arg("MATCH")
+                    .arg(&pattern)
+                    .arg("COUNT")
+                    .arg(SCAN_COUNT)
+                    .query_async(&mut conn)
+                    .await;
+                let Ok((next, keys)) = res else {
+                    cache_inc("redis", "err");
+                    break;
+                };
+                purged += self.delete(&keys).await;
+                if next == 0 {
+                    break;
+                }
+                cursor = next;
+            }
+            purged
         }
+        .boxed()
     }
 }
 
-pub async fn set_json<T: Serialize>(
-    conn: &mut ConnectionManager,
-    key: &str,
-    value: &T,
-    ttl_secs: u64,
-) -> Result<(), redis::RedisError> {
-    let buf = serde_json::to_vec(value)
-        .map_err(|_| redis::RedisError::from((redis::ErrorKind::TypeError, "serialize")))?;
-    let res = conn.set_ex(key, buf, ttl_secs as usize as u64).await;
-    match &res {
-        Ok(_) => cache_inc("set", "ok"),
-        Err(_) => cache_inc("set", "err"),
+fn escape_glob(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
     }
-    res
+    out
 }
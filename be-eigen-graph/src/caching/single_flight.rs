@@ -0,0 +1,20 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+
+static KEY_LOCKS: Lazy<Mutex<HashMap<String, Weak<tokio::sync::Mutex<()>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Returns the process-wide lock for `key`. Holding it marks a fetch for that
+/// key as in flight, so concurrent misses wait for one origin request instead
+/// of each issuing their own.
+pub fn key_lock(key: &str) -> Arc<tokio::sync::Mutex<()>> {
+    let mut locks = KEY_LOCKS.lock().unwrap();
+    if let Some(lock) = locks.get(key).and_then(Weak::upgrade) {
+        return lock;
+    }
+    locks.retain(|_, w| w.strong_count() > 0);
+    let lock = Arc::new(tokio::sync::Mutex::new(()));
+    locks.insert(key.to_string(), Arc::downgrade(&lock));
+    lock
+}
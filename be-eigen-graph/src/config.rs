@@ -1,21 +1,70 @@
+use crate::api::subgraph::tape::SubgraphMode;
 use reqwest::Url;
 use std::env;
 use std::str::FromStr;
 
+/// Only tokens redeemable 1:1 for the quoted asset. Liquid staking tokens trade
+/// at their own (usually non-1) rate to ETH, so they stay unpriced unless
+/// `PRICE_PRODUCTS` maps them to a product of their own.
+const DEFAULT_PRICE_PRODUCTS: &str = "ETH=ETH-USD,WETH=ETH-USD,EIGEN=EIGEN-USD,bEIGEN=EIGEN-USD";
+
 #[derive(Debug, Clone)]
 pub struct AppConfig {
     pub subgraph_url: Url,
     pub database_url: String,
     pub redis_url: String,
     pub redis_ttl_seconds: u64,
+    pub redis_stale_seconds: u64,
+    pub memory_cache_capacity: usize,
+    pub memory_cache_ttl_seconds: u64,
+    pub app_env: String,
+    pub admin_token: Option<String>,
+    pub ingest_enabled: bool,
+    pub ingest_interval_seconds: u64,
+    pub ingest_page_size: i32,
+    pub subgraph_connect_timeout_ms: u64,
+    pub subgraph_request_timeout_ms: u64,
+    pub subgraph_max_retries: u32,
+    pub subgraph_backoff_base_ms: u64,
+    pub subgraph_backoff_max_ms: u64,
+    pub subgraph_breaker_threshold: u32,
+    pub subgraph_breaker_cooldown_seconds: u64,
+    pub subgraph_mode: SubgraphMode,
+    pub subgraph_tape_dir: String,
+    pub timescale_database_url: Option<String>,
+    pub price_products: String,
+    pub fixture_snapshot_path: Option<String>,
+    pub webhook_urls: Vec<Url>,
+    pub webhook_secret: Option<String>,
+    pub webhook_timeout_ms: u64,
+    pub webhook_max_attempts: u32,
+    pub webhook_backoff_base_ms: u64,
+    pub webhook_backoff_max_ms: u64,
+    pub alert_hhi_threshold: f64,
+    pub alert_recent_window_seconds: i64,
 }
 
 impl AppConfig {
     pub fn from_env() -> Self {
         dotenvy::dotenv().ok();
 
+        let subgraph_mode = match env::var("SUBGRAPH_MODE") {
+            Ok(s) => s
+                .parse::<SubgraphMode>()
+                .unwrap_or_else(|e| panic!("invalid SUBGRAPH_MODE: {e}")),
+            Err(_) => SubgraphMode::default(),
+        };
+        let subgraph_tape_dir =
+            env::var("SUBGRAPH_TAPE_DIR").unwrap_or_else(|_| "tapes".to_string());
         let subgraph = env::var("SUBGRAPH_URL").unwrap_or_default();
-        let subgraph_url = Url::from_str(&subgraph).unwrap();
+        // replay never touches the network, so the endpoint may be left unset
+        let subgraph_url = match Url::from_str(&subgraph) {
+            Ok(url) => url,
+            Err(_) if subgraph_mode == SubgraphMode::Replay => {
+                Url::from_str("http://replay.invalid/").unwrap()
+            }
+            Err(e) => panic!("invalid SUBGRAPH_URL: {e}"),
+        };
         let database_url = env::var("DATABASE_URL").unwrap_or_default();
         let redis_url =
             env::var("REDIS_URL").unwrap_or_else(|_| "redis://redis-eigen-graph:6379".to_string());
@@ -23,12 +72,135 @@ impl AppConfig {
             .ok()
             .and_then(|s| s.parse::<u64>().ok())
             .unwrap_or(60);
+        let redis_stale_seconds = env::var("REDIS_STALE_SECONDS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(300);
+        let memory_cache_capacity = env::var("MEMORY_CACHE_CAPACITY")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(256);
+        let memory_cache_ttl_seconds = env::var("MEMORY_CACHE_TTL_SECONDS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(10);
+        let app_env = env::var("APP_ENV").unwrap_or_else(|_| "dev".to_string());
+        let admin_token = env::var("ADMIN_TOKEN").ok().filter(|s| !s.is_empty());
+        let ingest_enabled = env::var("INGEST_ENABLED")
+            .ok()
+            .and_then(|s| s.parse::<bool>().ok())
+            .unwrap_or(true);
+        let ingest_interval_seconds = env::var("INGEST_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(300);
+        let ingest_page_size = env::var("INGEST_PAGE_SIZE")
+            .ok()
+            .and_then(|s| s.parse::<i32>().ok())
+            .unwrap_or(100);
+        let subgraph_connect_timeout_ms = env::var("SUBGRAPH_CONNECT_TIMEOUT_MS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(3_000);
+        let subgraph_request_timeout_ms = env::var("SUBGRAPH_REQUEST_TIMEOUT_MS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(15_000);
+        let subgraph_max_retries = env::var("SUBGRAPH_MAX_RETRIES")
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(3);
+        let subgraph_backoff_base_ms = env::var("SUBGRAPH_BACKOFF_BASE_MS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(200);
+        let subgraph_backoff_max_ms = env::var("SUBGRAPH_BACKOFF_MAX_MS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(5_000);
+        let subgraph_breaker_threshold = env::var("SUBGRAPH_BREAKER_THRESHOLD")
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(5);
+        let subgraph_breaker_cooldown_seconds = env::var("SUBGRAPH_BREAKER_COOLDOWN_SECONDS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(30);
+        let timescale_database_url = env::var("TIMESCALE_DATABASE_URL").ok();
+        let price_products =
+            env::var("PRICE_PRODUCTS").unwrap_or_else(|_| DEFAULT_PRICE_PRODUCTS.to_string());
+        let fixture_snapshot_path = env::var("FIXTURE_SNAPSHOT_PATH").ok();
+        let webhook_urls: Vec<Url> = env::var("WEBHOOK_URLS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                Url::from_str(s).unwrap_or_else(|e| panic!("invalid WEBHOOK_URLS entry {s}: {e}"))
+            })
+            .collect();
+        let webhook_secret = env::var("WEBHOOK_SECRET").ok().filter(|s| !s.is_empty());
+        if !webhook_urls.is_empty() && webhook_secret.is_none() {
+            panic!("WEBHOOK_URLS is set without WEBHOOK_SECRET");
+        }
+        let webhook_timeout_ms = env::var("WEBHOOK_TIMEOUT_MS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(5_000);
+        let webhook_max_attempts = env::var("WEBHOOK_MAX_ATTEMPTS")
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(5);
+        let webhook_backoff_base_ms = env::var("WEBHOOK_BACKOFF_BASE_MS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(1_000);
+        let webhook_backoff_max_ms = env::var("WEBHOOK_BACKOFF_MAX_MS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(60_000);
+        let alert_hhi_threshold = env::var("ALERT_HHI_THRESHOLD")
+            .ok()
+            .and_then(|s| s.parse::<f64>().ok())
+            .unwrap_or(0.2);
+        let alert_recent_window_seconds = env::var("ALERT_RECENT_WINDOW_SECONDS")
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .unwrap_or(7 * 24 * 3600);
 
         Self {
             subgraph_url,
             database_url,
             redis_url,
             redis_ttl_seconds,
+            redis_stale_seconds,
+            memory_cache_capacity,
+            memory_cache_ttl_seconds,
+            app_env,
+            admin_token,
+            ingest_enabled,
+            ingest_interval_seconds,
+            ingest_page_size,
+            subgraph_connect_timeout_ms,
+            subgraph_request_timeout_ms,
+            subgraph_max_retries,
+            subgraph_backoff_base_ms,
+            subgraph_backoff_max_ms,
+            subgraph_breaker_threshold,
+            subgraph_breaker_cooldown_seconds,
+            subgraph_mode,
+            subgraph_tape_dir,
+            timescale_database_url,
+            price_products,
+            fixture_snapshot_path,
+            webhook_urls,
+            webhook_secret,
+            webhook_timeout_ms,
+            webhook_max_attempts,
+            webhook_backoff_base_ms,
+            webhook_backoff_max_ms,
+            alert_hhi_threshold,
+            alert_recent_window_seconds,
         }
     }
 
@@ -0,0 +1,139 @@
+use crate::api::subgraph::errors::InfraError;
+use crate::metrics::error_inc;
+use axum::Json;
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("subgraph error: {0}")]
+    Subgraph(#[from] InfraError),
+
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+
+    #[error("bad request: {0}")]
+    BadRequest(String),
+
+    #[error("not found: {0}")]
+    NotFound(String),
+
+    #[error("unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProblemBody {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    title: &'static str,
+    status: u16,
+    detail: String,
+}
+
+impl ApiError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ApiError::Subgraph(InfraError::Http(e)) if e.is_timeout() => "subgraph_timeout",
+            ApiError::Subgraph(InfraError::Http(e)) if e.is_decode() => "subgraph_decode",
+            ApiError::Subgraph(InfraError::Http(_)) => "subgraph_http",
+            ApiError::Subgraph(InfraError::Decode(_)) => "subgraph_decode",
+            ApiError::Subgraph(InfraError::GraphQL(_)) => "subgraph_graphql",
+            ApiError::Subgraph(InfraError::EmptyData) => "subgraph_empty_data",
+            ApiError::Subgraph(InfraError::RateLimited(_)) => "subgraph_rate_limited",
+            ApiError::Subgraph(InfraError::CircuitOpen) => "subgraph_circuit_open",
+            ApiError::Subgraph(InfraError::TapeMiss(_)) => "subgraph_tape_miss",
+            ApiError::Subgraph(InfraError::Tape(_)) => "subgraph_tape",
+            ApiError::Db(sqlx::Error::RowNotFound) => "not_found",
+            ApiError::Db(sqlx::Error::PoolTimedOut) => "db_pool_timeout",
+            ApiError::Db(_) => "db_query",
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::Internal(_) => "internal",
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::Subgraph(InfraError::Http(e)) if e.is_timeout() => {
+                StatusCode::GATEWAY_TIMEOUT
+            }
+            ApiError::Subgraph(InfraError::CircuitOpen) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Subgraph(_) => StatusCode::BAD_GATEWAY,
+            ApiError::Db(sqlx::Error::RowNotFound) => StatusCode::NOT_FOUND,
+            ApiError::Db(sqlx::Error::PoolTimedOut) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Db(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let kind = self.kind();
+        let status = self.status();
+        error_inc(kind);
+
+        let detail = match &self {
+            ApiError::Db(_) | ApiError::Internal(_) => status
+                .canonical_reason()
+                .unwrap_or("internal error")
+                .to_string(),
+            other => other.to_string(),
+        };
+        let body = ProblemBody {
+            kind,
+            title: status.canonical_reason().unwrap_or("error"),
+            status: status.as_u16(),
+            detail,
+        };
+
+        (
+            status,
+            [(header::CONTENT_TYPE, "application/problem+json")],
+            Json(body),
+        )
+            .into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::extract::Query;
+    use axum::extract::FromRequestParts;
+    use axum::http::Request;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Paging {
+        #[allow(dead_code)]
+        first: Option<i32>,
+    }
+
+    #[tokio::test]
+    async fn query_rejections_are_problem_json() {
+        let (mut parts, _) = Request::get("/x?first=abc").body(()).unwrap().into_parts();
+        let Err(err) = Query::<Paging>::from_request_parts(&mut parts, &()).await else {
+            panic!("first=abc must be rejected");
+        };
+        assert_eq!(err.kind(), "bad_request");
+
+        let resp = err.into_response();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            resp.headers()[header::CONTENT_TYPE],
+            "application/problem+json"
+        );
+    }
+}
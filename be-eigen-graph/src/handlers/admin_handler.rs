@@ -0,0 +1,52 @@
+use crate::errors::ApiError;
+use crate::payloads::admin::{CachePurgeRequest, CachePurgeResponse};
+use crate::state::AppState;
+use axum::{
+    extract::State,
+    http::{HeaderMap, header},
+    response::Json,
+};
+
+pub async fn cache_purge_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<CachePurgeRequest>,
+) -> Result<Json<CachePurgeResponse>, ApiError> {
+    authorize(&state, &headers)?;
+
+    let purged = match (req.prefix.as_deref(), req.operator_id.as_deref()) {
+        (Some(prefix), None) => state.cache.purge_prefix(prefix).await,
+        (None, Some(id)) => state.cache.purge_operator(id).await,
+        _ => {
+            return Err(ApiError::BadRequest(
+                "exactly one of prefix or operatorId is required".to_string(),
+            ));
+        }
+    };
+
+    Ok(Json(CachePurgeResponse { purged }))
+}
+
+fn authorize(state: &AppState, headers: &HeaderMap) -> Result<(), ApiError> {
+    // without a configured token the endpoint does not exist
+    let Some(expected) = state.admin_token.as_deref() else {
+        return Err(ApiError::NotFound("route /admin/cache/purge".to_string()));
+    };
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or("");
+    if constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+        Ok(())
+    } else {
+        Err(ApiError::Unauthorized("invalid admin token".to_string()))
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,106 @@
+use crate::errors::ApiError;
+use crate::handlers::extract::Query;
+use crate::handlers::params::{parse_cursor, parse_first, parse_live_or_db};
+use crate::metrics::error_inc;
+use crate::models::avs::{AvsPageVars, UniformAvs};
+use crate::payloads::avs::{
+    AvsDetailQuery, AvsDetailView, AvsListMeta, AvsListQuery, AvsListResponse,
+};
+use crate::repositories::avs::{select_avs, select_avs_after, upsert_avs_page};
+use crate::services::avs::avs_fetcher::{avs_detail, avs_page_after};
+use crate::services::avs::avs_valuation::{registered_operator_ids, value_avs};
+use crate::services::operators::operators_aggr::from_db_adapt::from_db_operators;
+use crate::services::operators::operators_cursor::next_cursor_for;
+use crate::services::operators::operators_source::PageSourceKind;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use std::collections::BTreeSet;
+
+pub async fn avs_list_handler(
+    State(state): State<AppState>,
+    Query(q): Query<AvsListQuery>,
+) -> Result<Json<AvsListResponse>, ApiError> {
+    let source = parse_live_or_db(q.source.as_deref())?;
+    let first = parse_first(q.first)?;
+    let cursor = match q.after.as_deref() {
+        Some(after) => parse_cursor(after)?,
+        None => String::new(),
+    };
+
+    let (avss, next_cursor) = match source {
+        PageSourceKind::Db => {
+            let avss = select_avs_after(&state.db, first, &cursor).await?;
+            let next_cursor =
+                next_cursor_for(avss.len(), avss.last().map(|a| a.avs_id.as_str()), first);
+            (avss, next_cursor)
+        }
+        _ => {
+            let page =
+                avs_page_after(&state.subgraph_client, AvsPageVars { first, cursor }).await?;
+            let avss: Vec<UniformAvs> = page.avss.iter().map(UniformAvs::from).collect();
+            persist_avs(&state, &avss).await;
+            (avss, page.next_cursor)
+        }
+    };
+
+    let operator_ids: BTreeSet<String> = avss.iter().flat_map(registered_operator_ids).collect();
+    let operator_ids: Vec<String> = operator_ids.into_iter().collect();
+    let operators = from_db_operators(&state.db, &operator_ids).await?;
+
+    let now_ts = chrono::Utc::now().timestamp();
+    let items: Vec<_> = avss
+        .iter()
+        .map(|a| value_avs(a, &operators, now_ts).0)
+        .collect();
+
+    Ok(Json(AvsListResponse {
+        meta: AvsListMeta {
+            source: source.as_str().to_string(),
+            first,
+            count: items.len(),
+            next_cursor,
+        },
+        items,
+    }))
+}
+
+pub async fn avs_detail_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<AvsDetailQuery>,
+) -> Result<Json<AvsDetailView>, ApiError> {
+    let avs_id = id.to_lowercase();
+    let source = parse_live_or_db(q.source.as_deref())?;
+
+    let avs = match source {
+        PageSourceKind::Db => select_avs(&state.db, &avs_id).await?,
+        _ => match avs_detail(&state.subgraph_client, &avs_id).await? {
+            Some(dto) => {
+                let avs = UniformAvs::from(&dto);
+                persist_avs(&state, std::slice::from_ref(&avs)).await;
+                Some(avs)
+            }
+            None => None,
+        },
+    };
+    let avs = avs.ok_or_else(|| ApiError::NotFound(format!("avs {avs_id}")))?;
+
+    let operators = from_db_operators(&state.db, &registered_operator_ids(&avs)).await?;
+    let now_ts = chrono::Utc::now().timestamp();
+    let (view, operators) = value_avs(&avs, &operators, now_ts);
+
+    Ok(Json(AvsDetailView {
+        source: source.as_str().to_string(),
+        avs: view,
+        operators,
+    }))
+}
+
+async fn persist_avs(state: &AppState, avss: &[UniformAvs]) {
+    if let Err(_e) = upsert_avs_page(&state.db, avss).await {
+        error_inc("persist_avs_db");
+    }
+}
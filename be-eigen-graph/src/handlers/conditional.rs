@@ -0,0 +1,134 @@
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::{IntoResponse, Json, Response};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// Serializes `body` with an `ETag` computed over `tagged` and, when known, a
+/// `Last-Modified` taken from the newest block timestamp in the data. Answers
+/// `304 Not Modified` if the request's `If-None-Match` already names the tag.
+///
+/// `tagged` is usually the payload without volatile envelope fields such as
+/// `ageSeconds`, so the tag only changes when the data does.
+pub fn conditional_json<B: Serialize, T: Serialize>(
+    headers: &HeaderMap,
+    body: &B,
+    tagged: &T,
+    last_modified_ts: Option<i64>,
+) -> Response {
+    let Ok(bytes) = serde_json::to_vec(tagged) else {
+        return Json(body).into_response();
+    };
+    let etag = format!("\"{}\"", hex::encode(&Sha256::digest(&bytes)[..16]));
+    let last_modified = last_modified_ts.and_then(http_date);
+
+    let mut response = if if_none_match(headers, &etag) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        Json(body).into_response()
+    };
+
+    let h = response.headers_mut();
+    if let Ok(v) = HeaderValue::from_str(&etag) {
+        h.insert(header::ETAG, v);
+    }
+    if let Some(v) = last_modified.and_then(|s| HeaderValue::from_str(&s).ok()) {
+        h.insert(header::LAST_MODIFIED, v);
+    }
+    h.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    response
+}
+
+fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    let Some(value) = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+    else {
+        return false;
+    };
+    value.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+    })
+}
+
+fn http_date(ts: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|t| t.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn request_with(if_none_match: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(v) = if_none_match {
+            headers.insert(header::IF_NONE_MATCH, HeaderValue::from_str(v).unwrap());
+        }
+        headers
+    }
+
+    fn etag_of(response: &Response) -> String {
+        response.headers()[header::ETAG]
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn fresh_request_gets_body_and_validators() {
+        let data = json!({"a": 1});
+        let response = conditional_json(&request_with(None), &data, &data, Some(0));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::LAST_MODIFIED],
+            "Thu, 01 Jan 1970 00:00:00 GMT"
+        );
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-cache");
+    }
+
+    #[test]
+    fn matching_tag_is_not_modified() {
+        let data = json!({"a": 1});
+        let etag = etag_of(&conditional_json(&request_with(None), &data, &data, None));
+
+        for header_value in [
+            etag.clone(),
+            format!("W/{etag}"),
+            format!("\"x\", {etag}"),
+            "*".to_string(),
+        ] {
+            let response = conditional_json(&request_with(Some(&header_value)), &data, &data, None);
+            assert_eq!(
+                response.status(),
+                StatusCode::NOT_MODIFIED,
+                "{header_value}"
+            );
+            assert_eq!(etag_of(&response), etag);
+        }
+    }
+
+    #[test]
+    fn tag_ignores_untagged_envelope_fields() {
+        let tagged = json!({"rows": [1, 2]});
+        let first = conditional_json(
+            &request_with(None),
+            &json!({"ageSeconds": 1}),
+            &tagged,
+            None,
+        );
+        let later = conditional_json(
+            &request_with(None),
+            &json!({"ageSeconds": 9}),
+            &tagged,
+            None,
+        );
+        assert_eq!(etag_of(&first), etag_of(&later));
+
+        let changed = conditional_json(&request_with(None), &tagged, &json!({"rows": [1]}), None);
+        assert_ne!(etag_of(&first), etag_of(&changed));
+        let stale = request_with(Some(&etag_of(&first)));
+        let response = conditional_json(&stale, &tagged, &json!({"rows": [1]}), None);
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
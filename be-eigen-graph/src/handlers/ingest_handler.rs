@@ -0,0 +1,24 @@
+use crate::errors::ApiError;
+use crate::models::ingest::IngestStatus;
+use crate::payloads::ingest::IngestStatusView;
+use crate::repositories::ingest_runs::last_ingest_run;
+use crate::services::ingest::operators_ingest::OPERATORS_INGEST_KIND;
+use crate::state::AppState;
+use axum::{extract::State, response::Json};
+
+pub async fn ingest_status_handler(
+    State(state): State<AppState>,
+) -> Result<Json<IngestStatusView>, ApiError> {
+    let last_success = last_ingest_run(
+        &state.db,
+        OPERATORS_INGEST_KIND,
+        Some(IngestStatus::Succeeded),
+    )
+    .await?;
+    let latest = last_ingest_run(&state.db, OPERATORS_INGEST_KIND, None).await?;
+
+    Ok(Json(IngestStatusView {
+        last_success,
+        latest,
+    }))
+}
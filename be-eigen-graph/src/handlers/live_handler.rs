@@ -0,0 +1,64 @@
+use crate::handlers::extract::Query;
+use crate::metrics::error_inc;
+use crate::payloads::operators::LiveQuery;
+use crate::services::live::live_view::LiveFilter;
+use crate::state::AppState;
+use axum::extract::State;
+use axum::extract::ws::{Message, Utf8Bytes, WebSocket, WebSocketUpgrade};
+use axum::response::IntoResponse;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures::Stream;
+use std::convert::Infallible;
+
+pub async fn aggregates_ws_handler(
+    State(state): State<AppState>,
+    Query(q): Query<LiveQuery>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let filter = LiveFilter::from_query(&q);
+    ws.on_upgrade(move |socket| stream_ws(socket, state, filter))
+}
+
+pub async fn aggregates_sse_handler(
+    State(state): State<AppState>,
+    Query(q): Query<LiveQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let sub = state.live.subscribe(LiveFilter::from_query(&q)).await;
+    let events = futures::stream::unfold(sub, |mut sub| async move {
+        let msg = sub.next().await?;
+        let event = Event::default()
+            .event(msg.kind())
+            .json_data(&msg)
+            .unwrap_or_else(|_e| {
+                error_inc("live_encode");
+                Event::default().comment("encode error")
+            });
+        Some((Ok(event), sub))
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+async fn stream_ws(mut socket: WebSocket, state: AppState, filter: LiveFilter) {
+    let mut sub = state.live.subscribe(filter).await;
+    loop {
+        tokio::select! {
+            msg = sub.next() => {
+                let Some(msg) = msg else {
+                    break;
+                };
+                let Ok(text) = serde_json::to_string(&msg) else {
+                    error_inc("live_encode");
+                    continue;
+                };
+                if socket.send(Message::Text(Utf8Bytes::from(text))).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // the stream is one-way; anything else the client sends is ignored
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
@@ -1,2 +1,12 @@
+pub mod admin_handler;
+pub mod avs_handler;
+pub mod conditional;
+pub mod extract;
+pub mod ingest_handler;
+pub mod live_handler;
+pub mod network_handler;
 pub mod operators_cached_handler;
 pub mod operators_handler;
+pub mod params;
+pub mod strategies_handler;
+pub mod tokens_handler;
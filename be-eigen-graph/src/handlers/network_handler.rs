@@ -0,0 +1,16 @@
+use crate::errors::ApiError;
+use crate::models::operators_aggr::NetworkSummary;
+use crate::services::concentration::network_summary::network_summary;
+use crate::services::operators::operators_aggr::from_db_adapt::from_db_adapt_all;
+use crate::state::AppState;
+use axum::{extract::State, response::Json};
+
+pub async fn network_summary_handler(
+    State(state): State<AppState>,
+) -> Result<Json<NetworkSummary>, ApiError> {
+    let uniform = from_db_adapt_all(&state.db).await?;
+    let prices = state.prices.latest().await;
+    let now_ts = chrono::Utc::now().timestamp();
+
+    Ok(Json(network_summary(&uniform, &prices, now_ts)))
+}
@@ -1,67 +1,102 @@
-use crate::metrics::error_inc;
+use crate::api::subgraph::errors::InfraError;
+use crate::errors::ApiError;
+use crate::handlers::conditional::conditional_json;
+use crate::handlers::extract::Query;
+use crate::handlers::params::{
+    parse_as_of, parse_cursor, parse_first, parse_history_range, parse_interval, parse_live_or_db,
+    parse_order_by, parse_order_direction, parse_skip,
+};
+use crate::models::ids::TokenId;
 use crate::models::operators_aggr::AggregatorParams;
-use crate::models::operators_snapshot::{OperatorOrderBy, OperatorsSnapshotVars, OrderDirection};
+use crate::models::operators_snapshot::{
+    OperatorsSnapshotCursorVars, OperatorsSnapshotData, OperatorsSnapshotVars, OrderDirection,
+};
+use crate::payloads::operators::AggregatesQuery;
 use crate::payloads::operators::SnapshotQuery;
-use crate::payloads::operators::TokenSlice;
-use crate::payloads::operators::{AggregatesMeta, AggregatesQuery, AggregatesResponse};
-use crate::services::operators::operators_aggr::operators_part::partition_by_token;
-use crate::services::operators::operators_aggr::{
-    from_db_adapt, from_subgraph_adapt, operators_aggregator,
+use crate::payloads::operators::{DiffSideMeta, OperatorsDiffQuery, OperatorsDiffResponse};
+use crate::payloads::operators::{OperatorDetailQuery, OperatorDetailView};
+use crate::payloads::operators::{
+    OperatorHistoryQuery, OperatorHistoryResponse, StrategyHistoryPointView, StrategyHistorySeries,
+};
+use crate::payloads::operators::{
+    OperatorRiskItemView, OperatorRiskMeta, OperatorRiskQuery, OperatorRiskResponse,
+};
+use crate::services::operators::operator_detail::{
+    build_operator_detail_view, operator_detail_db, operator_detail_from_dto, operator_detail_live,
+};
+use crate::services::operators::operators_aggr::aggregates_response::build_aggregates_response;
+use crate::services::operators::operators_diff::{
+    DiffPoint, DiffSide, diff_by_token, diff_pages, load_diff_side,
 };
-use crate::services::operators::operators_fetcher::operators_snapshot;
+use crate::services::operators::operators_fetcher::{operators_snapshot, operators_snapshot_after};
+use crate::services::operators::operators_filter::{
+    OperatorRiskOrder, OperatorRiskOrderField, OperatorRiskParams, SortDir, list_operator_risk,
+};
+use crate::services::operators::operators_history::{operator_history_db, resolve_as_of_block};
+use crate::services::operators::operators_repo::persist_head_snapshot;
+use crate::services::operators::operators_source::{PageRequest, PageSourceKind, page_source_for};
 use crate::state::AppState;
 use axum::{
-    extract::{Query, State},
-    response::{IntoResponse, Json},
+    extract::{Path, State},
+    http::HeaderMap,
+    response::{Json, Response},
 };
-use std::collections::BTreeMap;
 
 pub async fn snapshot_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Query(q): Query<SnapshotQuery>,
-) -> impl IntoResponse {
-    let vars = OperatorsSnapshotVars {
-        first: q.first.unwrap_or(25),
-        skip: q.skip.unwrap_or(0),
-        has_slashing: q.has_slashing.unwrap_or(0),
-        order_by: parse_order_by(q.order_by.as_deref())
-            .unwrap_or(OperatorOrderBy::LastUpdateBlockTimestamp),
-        order_direction: match q.order_direction.as_deref() {
-            Some("asc") => OrderDirection::Asc,
-            _ => OrderDirection::Desc,
-        },
+) -> Result<Response, ApiError> {
+    let first = parse_first(q.first)?;
+    let has_slashing = q.has_slashing.unwrap_or(0);
+    let as_of = parse_as_of(q.as_of_block, q.as_of_timestamp)?;
+    let block = match as_of {
+        Some(as_of) => Some(resolve_as_of_block(&state.db, as_of).await?),
+        None => None,
     };
 
-    let data = operators_snapshot(
-        state.subgraph_client.http.clone(),
-        state.subgraph_client.endpoint.clone(),
-        vars,
-    )
-    .await
-    .expect("subgraph query failed");
-
-    if let Err(_e) =
-        crate::services::operators::operators_repo::persist_operators_snapshot_db(&state.db, &data)
-            .await
-    {
-        error_inc("persist_operators_snapshot_db");
-    }
+    let data = match q.after.as_deref() {
+        Some(after) => {
+            let cursor = parse_cursor(after)?;
+            let vars = OperatorsSnapshotCursorVars {
+                first,
+                cursor,
+                has_slashing,
+                block,
+            };
+            operators_snapshot_after(&state.subgraph_client, vars).await?
+        }
+        None => {
+            let vars = OperatorsSnapshotVars {
+                first,
+                skip: parse_skip(q.skip)?,
+                has_slashing,
+                order_by: parse_order_by(q.order_by.as_deref())?,
+                order_direction: parse_order_direction(q.order_direction.as_deref())?,
+                block,
+            };
+            operators_snapshot(&state.subgraph_client, vars).await?
+        }
+    };
 
-    crate::services::operators::operators_cache::upsert_operators_snapshot_cache(
-        &state.operators_snapshot,
-        &data,
-    );
+    persist_head_snapshot(&state.db, as_of, &data).await;
 
-    Json(data)
+    let last_modified = data.max_last_update_ts();
+    Ok(conditional_json(&headers, &data, &data, last_modified))
 }
 
 pub async fn operators_aggregates_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Query(q): Query<AggregatesQuery>,
-) -> impl IntoResponse {
-    let source = q.source.as_deref().unwrap_or("live");
-    let first = q.first.unwrap_or(25);
-    let skip = q.skip.unwrap_or(0);
+) -> Result<Response, ApiError> {
+    let kind = PageSourceKind::parse_or(q.source.as_deref(), PageSourceKind::Live)?;
+    let req = PageRequest {
+        first: parse_first(q.first)?,
+        skip: parse_skip(q.skip)?,
+        cursor: q.after.as_deref().map(parse_cursor).transpose()?,
+        as_of: parse_as_of(q.as_of_block, q.as_of_timestamp)?,
+    };
 
     let params = AggregatorParams {
         top_n: q.top_n.unwrap_or(10).clamp(1, 100),
@@ -71,139 +106,239 @@ pub async fn operators_aggregates_handler(
         focus_operator_id: q.operator_id.clone(),
     };
 
-    let uniform = match source {
-        "db" => {
-            // DB adapter
-            from_db_adapt::from_db_adapt(&state.db, first, skip)
-                .await
-                .unwrap_or_else(|_| crate::models::operators_aggr::UniformPage {
-                    operators: vec![],
-                    page_meta: crate::models::operators_aggr::PageMeta { first, skip },
-                })
-        }
-        _ => {
-            let vars = OperatorsSnapshotVars {
-                first,
-                skip,
-                has_slashing: 0,
-                order_by: OperatorOrderBy::LastUpdateBlockTimestamp,
-                order_direction: OrderDirection::Desc,
-            };
-            let page = operators_snapshot(
-                state.subgraph_client.http.clone(),
-                state.subgraph_client.endpoint.clone(),
-                vars,
-            )
-            .await
-            .expect("subgraph query failed");
-
-            from_subgraph_adapt::from_subgraph_adapt(&page, first, skip)
-        }
+    let page = page_source_for(&state, kind)?.load(req).await?;
+    let prices = state.prices.latest().await;
+    let now_ts = chrono::Utc::now().timestamp();
+    let resp = build_aggregates_response(&page.data, &params, &prices, kind.as_str(), now_ts);
+
+    let last_modified = resp.max_last_update_ts();
+    Ok(conditional_json(&headers, &resp, &resp, last_modified))
+}
+
+pub async fn operator_detail_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<OperatorDetailQuery>,
+) -> Result<Json<OperatorDetailView>, ApiError> {
+    let operator_id = id.to_lowercase();
+    let source = parse_live_or_db(q.source.as_deref())?;
+
+    let detail = match source {
+        PageSourceKind::Db => operator_detail_db(&state.db, &operator_id).await?,
+        _ => match operator_detail_live(&state.subgraph_client, &operator_id).await? {
+            Some(op) => {
+                let data = OperatorsSnapshotData {
+                    operators: vec![op],
+                    next_cursor: None,
+                    meta: None,
+                };
+                persist_head_snapshot(&state.db, None, &data).await;
+                data.operators
+                    .into_iter()
+                    .next()
+                    .and_then(operator_detail_from_dto)
+            }
+            None => None,
+        },
     };
 
+    let params = AggregatorParams {
+        hhi_threshold: q.hhi_threshold.unwrap_or(0.2),
+        ..AggregatorParams::default()
+    };
     let now_ts = chrono::Utc::now().timestamp();
-    let aggregates = operators_aggregator::aggregate(&uniform, &params, now_ts);
-
-    let table = operators_aggregator::to_table_rows(&aggregates);
-    let bar = operators_aggregator::to_bar_series(&aggregates, params.top_n);
-    let donuts_vec = operators_aggregator::to_donuts(&aggregates, &params.focus_operator_id);
-    let donut = if params.focus_operator_id.is_some() {
-        if let Some(d) = donuts_vec.into_iter().next() {
-            serde_json::to_value(d).unwrap_or(serde_json::json!({}))
-        } else {
-            serde_json::json!({})
+
+    detail
+        .and_then(|d| build_operator_detail_view(d, source.as_str(), &params, now_ts))
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("operator {operator_id}")))
+}
+
+const HISTORY_MAX_POINTS: i64 = 2000;
+const HISTORY_DEFAULT_WINDOW_SECS: i64 = 30 * 24 * 3600;
+
+pub async fn operator_history_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<OperatorHistoryQuery>,
+) -> Result<Json<OperatorHistoryResponse>, ApiError> {
+    let operator_id = id.to_lowercase();
+    let interval_secs = parse_interval(q.interval.as_deref())?;
+    let (from, to) = parse_history_range(
+        q.from,
+        q.to,
+        chrono::Utc::now().timestamp(),
+        HISTORY_DEFAULT_WINDOW_SECS,
+        interval_secs,
+        HISTORY_MAX_POINTS,
+    )?;
+
+    let (points, strategy_points) =
+        operator_history_db(&state.db, &operator_id, from, to, interval_secs).await?;
+
+    // rows arrive ordered by strategy, so each series is a contiguous run
+    let mut strategies: Vec<StrategyHistorySeries> = Vec::new();
+    for p in strategy_points {
+        let point = StrategyHistoryPointView {
+            bucket_ts: p.bucket_ts,
+            captured_at: p.captured_at,
+            total_shares: p.total_shares,
+            exchange_rate: p.exchange_rate,
+            tvl_atomic: p.tvl_atomic,
+        };
+        match strategies.last_mut() {
+            Some(series) if series.strategy_id == p.strategy_id => series.points.push(point),
+            _ => strategies.push(StrategyHistorySeries {
+                strategy_id: p.strategy_id,
+                token_id: p.token_id,
+                token_symbol: p.token_symbol,
+                token_decimals: p.token_decimals,
+                points: vec![point],
+            }),
         }
-    } else {
-        let map = donuts_vec
-            .into_iter()
-            .map(|d| (d.operator_id.clone(), d))
-            .collect::<std::collections::BTreeMap<_, _>>();
-        serde_json::to_value(map).unwrap_or(serde_json::json!({}))
+    }
+
+    Ok(Json(OperatorHistoryResponse {
+        operator_id,
+        from,
+        to,
+        interval_secs,
+        points,
+        strategies,
+    }))
+}
+
+const DIFF_DEFAULT_WINDOW_SECS: i64 = 24 * 3600;
+
+pub async fn operators_diff_handler(
+    State(state): State<AppState>,
+    Query(q): Query<OperatorsDiffQuery>,
+) -> Result<Json<OperatorsDiffResponse>, ApiError> {
+    let now_ts = chrono::Utc::now().timestamp();
+    let to = parse_diff_point(q.to.as_deref())?.unwrap_or(DiffPoint::Live);
+    let to_ts = match to {
+        DiffPoint::Stored(ts) => ts,
+        DiffPoint::Live => now_ts,
     };
-    let graph = operators_aggregator::to_graph_edges(&aggregates);
-    let outliers = operators_aggregator::detect_outliers(
-        &aggregates,
-        params.hhi_threshold,
-        params.recent_window_s,
-        now_ts,
-    );
-
-    let mut by_token: BTreeMap<String, TokenSlice> = BTreeMap::new();
-    let token_pages = partition_by_token(&uniform);
-    for (symbol, token_page) in token_pages {
-        let aggr_tok = operators_aggregator::aggregate(&token_page, &params, now_ts);
-
-        let table_tok = operators_aggregator::to_table_rows(&aggr_tok);
-        let bar_tok = operators_aggregator::to_bar_series(&aggr_tok, params.top_n);
-        let donuts_tok = operators_aggregator::to_donuts(&aggr_tok, &params.focus_operator_id);
-        let donut_tok = if params.focus_operator_id.is_some() {
-            if let Some(d) = donuts_tok.into_iter().next() {
-                serde_json::to_value(d).unwrap_or(serde_json::json!({}))
-            } else {
-                serde_json::json!({})
-            }
-        } else {
-            let map = donuts_tok
-                .into_iter()
-                .map(|d| (d.operator_id.clone(), d))
-                .collect::<BTreeMap<_, _>>();
-            serde_json::to_value(map).unwrap_or(serde_json::json!({}))
-        };
-        let graph_tok = operators_aggregator::to_graph_edges(&aggr_tok);
-        let out_tok = operators_aggregator::detect_outliers(
-            &aggr_tok,
-            params.hhi_threshold,
-            params.recent_window_s,
-            now_ts,
-        );
-
-        let meta_tok = AggregatesMeta {
-            source: source.to_string(),
-            first,
-            skip,
-            count: aggr_tok.len(),
-        };
+    let from = parse_diff_point(q.from.as_deref())?
+        .unwrap_or(DiffPoint::Stored(to_ts - DIFF_DEFAULT_WINDOW_SECS));
+    match (from, to) {
+        (DiffPoint::Stored(f), DiffPoint::Stored(t)) if f < t => {}
+        (DiffPoint::Stored(_), DiffPoint::Live) => {}
+        _ => {
+            return Err(ApiError::BadRequest(
+                "from must be a timestamp before to".to_string(),
+            ));
+        }
+    }
+    let top_n = q.top_n.unwrap_or(10).clamp(1, 100);
+
+    let before = load_diff_side(&state.db, from).await?;
+    let after = load_diff_side(&state.db, to).await?;
+    let prices = state.prices.latest().await;
 
-        by_token.insert(
-            symbol,
-            TokenSlice {
-                meta: meta_tok,
-                table: table_tok,
-                bar: bar_tok,
-                donut: donut_tok,
-                graph: graph_tok,
-                outliers: out_tok,
-            },
-        );
+    Ok(Json(OperatorsDiffResponse {
+        from: diff_side_meta(&before, from),
+        to: diff_side_meta(&after, to),
+        diff: diff_pages(&before.page, &after.page, &prices, top_n, now_ts),
+        by_token: diff_by_token(&before.page, &after.page, top_n, now_ts),
+    }))
+}
+
+fn diff_side_meta(side: &DiffSide, point: DiffPoint) -> DiffSideMeta {
+    DiffSideMeta {
+        source: side.source.to_string(),
+        timestamp: match point {
+            DiffPoint::Stored(ts) => Some(ts),
+            DiffPoint::Live => None,
+        },
+        captured_at: side.captured_at,
+        block: side.page.page_meta.block,
+        count: side.page.operators.len(),
+    }
+}
+
+pub async fn operators_risk_handler(
+    State(state): State<AppState>,
+    Query(q): Query<OperatorRiskQuery>,
+) -> Result<Json<OperatorRiskResponse>, ApiError> {
+    let token_filter = q.token.as_deref().map(|t| TokenId(t.to_lowercase()));
+    let field = parse_risk_order_field(q.order_by.as_deref())?;
+    if matches!(field, OperatorRiskOrderField::Tvl) && token_filter.is_none() {
+        return Err(ApiError::BadRequest(
+            "orderBy=tvl requires a token".to_string(),
+        ));
+    }
+    if q.min_tvl_atomic.is_some() && token_filter.is_none() {
+        return Err(ApiError::BadRequest(
+            "minTvlAtomic requires a token".to_string(),
+        ));
     }
+    let direction = match parse_order_direction(q.order_direction.as_deref())? {
+        OrderDirection::Asc => SortDir::Asc,
+        OrderDirection::Desc => SortDir::Desc,
+    };
 
-    let resp = AggregatesResponse {
-        meta: AggregatesMeta {
-            source: source.to_string(),
+    let first = parse_first(q.first)?;
+    let skip = parse_skip(q.skip)?;
+    let params = OperatorRiskParams {
+        first,
+        skip,
+        has_slashing: q.has_slashing.unwrap_or(0),
+        min_avs: q.min_avs,
+        concentration_max: q.concentration_max,
+        token_filter,
+        min_tvl_atomic: q.min_tvl_atomic,
+        order: OperatorRiskOrder { field, direction },
+    };
+
+    let page = list_operator_risk(&state.subgraph_client, params)
+        .await
+        .map_err(|e| match e.downcast::<InfraError>() {
+            Ok(infra) => ApiError::Subgraph(infra),
+            Err(e) => ApiError::Internal(e),
+        })?;
+
+    Ok(Json(OperatorRiskResponse {
+        meta: OperatorRiskMeta {
+            scope: "page".to_string(),
             first,
             skip,
-            count: aggregates.len(),
+            count: page.rows.len(),
         },
-        table,
-        bar,
-        donut,
-        graph,
-        outliers,
-        by_token,
-    };
+        items: page
+            .rows
+            .into_iter()
+            .map(OperatorRiskItemView::from)
+            .collect(),
+        next_skip: page.next_skip,
+        has_more: page.has_more,
+    }))
+}
 
-    Json(resp)
+fn parse_risk_order_field(s: Option<&str>) -> Result<OperatorRiskOrderField, ApiError> {
+    match s.unwrap_or("lastUpdateBlockTs") {
+        "tvl" => Ok(OperatorRiskOrderField::Tvl),
+        "hhi" => Ok(OperatorRiskOrderField::Hhi),
+        "avsCount" => Ok(OperatorRiskOrderField::AvsCount),
+        "strategyCount" => Ok(OperatorRiskOrderField::StrategyCount),
+        "slashingCount" => Ok(OperatorRiskOrderField::SlashingCount),
+        "lastSlashAt" => Ok(OperatorRiskOrderField::LastSlashAt),
+        "lastUpdateBlockTs" => Ok(OperatorRiskOrderField::LastUpdateBlockTs),
+        other => Err(ApiError::BadRequest(format!("unknown orderBy: {other}"))),
+    }
 }
 
-fn parse_order_by(s: Option<&str>) -> Option<OperatorOrderBy> {
-    match s? {
-        "id" => Some(OperatorOrderBy::Id),
-        "avsCount" | "avs_count" => Some(OperatorOrderBy::AvsCount),
-        "strategyCount" | "strategy_count" => Some(OperatorOrderBy::StrategyCount),
-        "slashingCount" | "slashing_count" => Some(OperatorOrderBy::SlashingCount),
-        "lastUpdateBlockTimestamp" | "last_update_block_timestamp" => {
-            Some(OperatorOrderBy::LastUpdateBlockTimestamp)
-        }
-        _ => None,
+/// A unix timestamp selects retained history; `live` selects the current mirror.
+fn parse_diff_point(s: Option<&str>) -> Result<Option<DiffPoint>, ApiError> {
+    match s {
+        None => Ok(None),
+        Some("live") => Ok(Some(DiffPoint::Live)),
+        Some(raw) => match raw.parse::<i64>() {
+            Ok(ts) if ts >= 0 => Ok(Some(DiffPoint::Stored(ts))),
+            _ => Err(ApiError::BadRequest(format!(
+                "invalid diff point: {raw} (expected a unix timestamp or live)"
+            ))),
+        },
     }
 }
@@ -0,0 +1,207 @@
+use crate::errors::ApiError;
+use crate::models::operators_snapshot::{AsOf, OperatorOrderBy, OrderDirection};
+use crate::services::operators::operators_cursor::decode_cursor;
+use crate::services::operators::operators_source::PageSourceKind;
+
+pub fn parse_first(first: Option<i32>) -> Result<i32, ApiError> {
+    match first.unwrap_or(25) {
+        f @ 1..=1000 => Ok(f),
+        _ => Err(ApiError::BadRequest(
+            "first must be between 1 and 1000".to_string(),
+        )),
+    }
+}
+
+pub fn parse_skip(skip: Option<i32>) -> Result<i32, ApiError> {
+    match skip.unwrap_or(0) {
+        s if s >= 0 => Ok(s),
+        _ => Err(ApiError::BadRequest(
+            "skip must not be negative".to_string(),
+        )),
+    }
+}
+
+pub fn parse_cursor(after: &str) -> Result<String, ApiError> {
+    decode_cursor(after).ok_or_else(|| ApiError::BadRequest("invalid cursor".to_string()))
+}
+
+pub fn parse_order_by(s: Option<&str>) -> Result<OperatorOrderBy, ApiError> {
+    match s {
+        None => Ok(OperatorOrderBy::LastUpdateBlockTimestamp),
+        Some("id") => Ok(OperatorOrderBy::Id),
+        Some("avsCount" | "avs_count") => Ok(OperatorOrderBy::AvsCount),
+        Some("strategyCount" | "strategy_count") => Ok(OperatorOrderBy::StrategyCount),
+        Some("slashingCount" | "slashing_count") => Ok(OperatorOrderBy::SlashingCount),
+        Some("lastUpdateBlockTimestamp" | "last_update_block_timestamp") => {
+            Ok(OperatorOrderBy::LastUpdateBlockTimestamp)
+        }
+        Some(other) => Err(ApiError::BadRequest(format!("unknown orderBy: {other}"))),
+    }
+}
+
+pub fn parse_order_direction(s: Option<&str>) -> Result<OrderDirection, ApiError> {
+    match s {
+        None | Some("desc") => Ok(OrderDirection::Desc),
+        Some("asc") => Ok(OrderDirection::Asc),
+        Some(other) => Err(ApiError::BadRequest(format!(
+            "unknown orderDirection: {other}"
+        ))),
+    }
+}
+
+/// `asOfBlock` and `asOfTimestamp` are alternatives; neither means the head.
+pub fn parse_as_of(block: Option<i64>, timestamp: Option<i64>) -> Result<Option<AsOf>, ApiError> {
+    match (block, timestamp) {
+        (None, None) => Ok(None),
+        (Some(b), None) if b >= 0 => Ok(Some(AsOf::Block(b))),
+        (None, Some(ts)) if ts >= 0 => Ok(Some(AsOf::Timestamp(ts))),
+        (Some(_), Some(_)) => Err(ApiError::BadRequest(
+            "asOfBlock and asOfTimestamp are mutually exclusive".to_string(),
+        )),
+        _ => Err(ApiError::BadRequest(
+            "asOf must not be negative".to_string(),
+        )),
+    }
+}
+
+/// `source` for endpoints that read either the subgraph or the DB mirror;
+/// defaults to `live`.
+pub fn parse_live_or_db(s: Option<&str>) -> Result<PageSourceKind, ApiError> {
+    match PageSourceKind::parse_or(s, PageSourceKind::Live)? {
+        kind @ (PageSourceKind::Live | PageSourceKind::Db) => Ok(kind),
+        other => Err(ApiError::BadRequest(format!(
+            "source {} is not supported here (expected live or db)",
+            other.as_str()
+        ))),
+    }
+}
+
+/// Accepts `5m`, `1h`, `1d`, `1w` style durations or plain seconds.
+pub fn parse_interval(s: Option<&str>) -> Result<i64, ApiError> {
+    let raw = s.unwrap_or("1h").trim();
+    let (num, unit) = match raw.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => raw.split_at(i),
+        None => (raw, "s"),
+    };
+    let scale = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 24 * 3600,
+        "w" => 7 * 24 * 3600,
+        _ => 0,
+    };
+    match num.parse::<i64>().ok().and_then(|n| n.checked_mul(scale)) {
+        Some(secs) if secs >= 60 => Ok(secs),
+        _ => Err(ApiError::BadRequest(format!(
+            "invalid interval: {raw} (expected e.g. 5m, 1h, 1d; minimum 1m)"
+        ))),
+    }
+}
+
+/// Resolves a history window to `[from, to)`: `to` defaults to `now` and
+/// `from` to `default_window` before `to`. At most `max_points` buckets of
+/// `interval_secs` fit in the window.
+pub fn parse_history_range(
+    from: Option<i64>,
+    to: Option<i64>,
+    now: i64,
+    default_window: i64,
+    interval_secs: i64,
+    max_points: i64,
+) -> Result<(i64, i64), ApiError> {
+    let to = to.unwrap_or(now);
+    let from = match from {
+        Some(from) => from,
+        None => to
+            .checked_sub(default_window)
+            .ok_or_else(|| ApiError::BadRequest(format!("to out of range: {to}")))?,
+    };
+    if from < 0 {
+        return Err(ApiError::BadRequest(format!("from out of range: {from}")));
+    }
+    if from >= to {
+        return Err(ApiError::BadRequest("from must be before to".to_string()));
+    }
+    if (to - from) / interval_secs > max_points {
+        return Err(ApiError::BadRequest(format!(
+            "range too large for interval: at most {max_points} points"
+        )));
+    }
+    Ok((from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_is_bounded() {
+        assert_eq!(parse_first(None).unwrap(), 25);
+        assert_eq!(parse_first(Some(1000)).unwrap(), 1000);
+        assert!(parse_first(Some(0)).is_err());
+        assert!(parse_first(Some(1001)).is_err());
+    }
+
+    #[test]
+    fn skip_must_not_be_negative() {
+        assert_eq!(parse_skip(None).unwrap(), 0);
+        assert!(parse_skip(Some(-1)).is_err());
+    }
+
+    #[test]
+    fn as_of_params_are_exclusive() {
+        assert!(parse_as_of(None, None).unwrap().is_none());
+        assert!(matches!(
+            parse_as_of(Some(10), None).unwrap(),
+            Some(AsOf::Block(10))
+        ));
+        assert!(matches!(
+            parse_as_of(None, Some(20)).unwrap(),
+            Some(AsOf::Timestamp(20))
+        ));
+        assert!(parse_as_of(Some(10), Some(20)).is_err());
+        assert!(parse_as_of(Some(-1), None).is_err());
+    }
+
+    #[test]
+    fn intervals() {
+        assert_eq!(parse_interval(None).unwrap(), 3600);
+        assert_eq!(parse_interval(Some("5m")).unwrap(), 300);
+        assert_eq!(parse_interval(Some("1w")).unwrap(), 7 * 24 * 3600);
+        assert_eq!(parse_interval(Some("120")).unwrap(), 120);
+        assert!(parse_interval(Some("30s")).is_err());
+        assert!(parse_interval(Some("1y")).is_err());
+        assert!(parse_interval(Some("99999999999999999w")).is_err());
+    }
+
+    #[test]
+    fn history_range_defaults_and_bounds() {
+        assert_eq!(
+            parse_history_range(None, None, 10_000, 3_600, 60, 100).unwrap(),
+            (6_400, 10_000)
+        );
+        assert_eq!(
+            parse_history_range(Some(100), Some(200), 0, 3_600, 60, 100).unwrap(),
+            (100, 200)
+        );
+        assert!(parse_history_range(None, Some(i64::MIN), 0, 3_600, 60, 100).is_err());
+        assert!(parse_history_range(Some(-1), Some(200), 0, 3_600, 60, 100).is_err());
+        assert!(parse_history_range(Some(200), Some(200), 0, 3_600, 60, 100).is_err());
+        assert!(parse_history_range(Some(0), Some(60 * 101), 0, 3_600, 60, 100).is_err());
+    }
+
+    #[test]
+    fn live_or_db_sources() {
+        assert_eq!(parse_live_or_db(None).unwrap(), PageSourceKind::Live);
+        assert_eq!(parse_live_or_db(Some("db")).unwrap(), PageSourceKind::Db);
+        assert!(parse_live_or_db(Some("typo")).is_err());
+        assert!(parse_live_or_db(Some("fixture")).is_err());
+    }
+
+    #[test]
+    fn unknown_order_values_are_rejected() {
+        assert!(parse_order_by(Some("tvl")).is_err());
+        assert!(parse_order_direction(Some("up")).is_err());
+    }
+}
@@ -0,0 +1,68 @@
+use crate::errors::ApiError;
+use crate::handlers::extract::Query;
+use crate::payloads::strategies::{
+    StrategiesMeta, StrategiesQuery, StrategiesResponse, StrategyDetailView, StrategyView,
+};
+use crate::services::operators::operators_aggr::from_db_adapt::{
+    from_db_adapt_all, from_db_strategy_operators,
+};
+use crate::services::operators::operators_aggr::strategies_aggregator::aggregate_strategies;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+
+pub async fn strategies_handler(
+    State(state): State<AppState>,
+    Query(q): Query<StrategiesQuery>,
+) -> Result<Json<StrategiesResponse>, ApiError> {
+    let hhi_threshold = parse_hhi_threshold(q.hhi_threshold)?;
+
+    let uniform = from_db_adapt_all(&state.db).await?;
+    let now_ts = chrono::Utc::now().timestamp();
+    let items: Vec<StrategyView> = aggregate_strategies(&uniform, now_ts)
+        .iter()
+        .map(|a| StrategyView::from_aggregate(a, hhi_threshold))
+        .collect();
+
+    Ok(Json(StrategiesResponse {
+        meta: StrategiesMeta {
+            source: "db".to_string(),
+            count: items.len(),
+            operator_count: uniform.operators.len(),
+            hhi_threshold,
+        },
+        items,
+    }))
+}
+
+pub async fn strategy_detail_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<StrategiesQuery>,
+) -> Result<Json<StrategyDetailView>, ApiError> {
+    let strategy_id = id.to_lowercase();
+    let hhi_threshold = parse_hhi_threshold(q.hhi_threshold)?;
+
+    let uniform = from_db_strategy_operators(&state.db, &strategy_id).await?;
+    let now_ts = chrono::Utc::now().timestamp();
+    let aggregate = aggregate_strategies(&uniform, now_ts)
+        .into_iter()
+        .find(|a| a.strategy_id == strategy_id)
+        .ok_or_else(|| ApiError::NotFound(format!("strategy {strategy_id}")))?;
+
+    Ok(Json(StrategyDetailView {
+        strategy: StrategyView::from_aggregate(&aggregate, hhi_threshold),
+        operators: aggregate.operator_breakdown,
+    }))
+}
+
+fn parse_hhi_threshold(hhi_threshold: Option<f64>) -> Result<f64, ApiError> {
+    match hhi_threshold.unwrap_or(0.2) {
+        t if (0.0..=1.0).contains(&t) => Ok(t),
+        _ => Err(ApiError::BadRequest(
+            "hhiThreshold must be between 0 and 1".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,23 @@
+use crate::errors::ApiError;
+use crate::payloads::tokens::{TokensMeta, TokensResponse};
+use crate::services::operators::operators_aggr::from_db_adapt::from_db_adapt_all;
+use crate::services::tokens::tokens_catalogue::build_token_catalogue;
+use crate::state::AppState;
+use axum::{extract::State, response::Json};
+
+pub async fn tokens_handler(
+    State(state): State<AppState>,
+) -> Result<Json<TokensResponse>, ApiError> {
+    let uniform = from_db_adapt_all(&state.db).await?;
+    let now_ts = chrono::Utc::now().timestamp();
+    let items = build_token_catalogue(&uniform, now_ts);
+
+    Ok(Json(TokensResponse {
+        meta: TokensMeta {
+            source: "db".to_string(),
+            count: items.len(),
+            operator_count: uniform.operators.len(),
+        },
+        items,
+    }))
+}
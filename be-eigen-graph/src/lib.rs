@@ -2,6 +2,7 @@ pub mod api;
 pub mod app;
 pub mod caching;
 pub mod config;
+pub mod errors;
 pub mod handlers;
 pub mod metrics;
 pub mod models;
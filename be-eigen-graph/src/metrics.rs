@@ -63,6 +63,15 @@ static CACHE_COUNTER: Lazy<IntCounterVec> = Lazy::new(|| {
     REGISTRY.register(Box::new(v.clone())).ok();
     v
 });
+static WEBHOOK_COUNTER: Lazy<IntCounterVec> = Lazy::new(|| {
+    let v = IntCounterVec::new(
+        opts!("webhook_deliveries_total", "webhook deliveries total"),
+        &["result"],
+    )
+    .unwrap();
+    REGISTRY.register(Box::new(v.clone())).ok();
+    v
+});
 static DB_HIST: Lazy<HistogramVec> = Lazy::new(|| {
     let v = HistogramVec::new(
         histogram_opts!("db_query_duration_seconds", "db query duration seconds"),
@@ -125,6 +134,10 @@ pub fn cache_inc(op: &'static str, result: &'static str) {
     CACHE_COUNTER.with_label_values(&[op, result]).inc();
 }
 
+pub fn webhook_inc(result: &'static str) {
+    WEBHOOK_COUNTER.with_label_values(&[result]).inc();
+}
+
 pub struct DbTimer {
     op: &'static str,
     start: Instant,
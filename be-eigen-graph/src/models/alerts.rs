@@ -0,0 +1,108 @@
+use serde::Serialize;
+
+/// The outlier lists of `detect_outliers`, one rule per list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertRule {
+    HighConcentration,
+    ZeroShare,
+    RecentSlash,
+}
+
+impl AlertRule {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertRule::HighConcentration => "high_concentration",
+            AlertRule::ZeroShare => "zero_share",
+            AlertRule::RecentSlash => "recent_slash",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "high_concentration" => Some(AlertRule::HighConcentration),
+            "zero_share" => Some(AlertRule::ZeroShare),
+            "recent_slash" => Some(AlertRule::RecentSlash),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transition {
+    Entered,
+    Left,
+}
+
+impl Transition {
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Transition::Entered => "outlier.entered",
+            Transition::Left => "outlier.left",
+        }
+    }
+}
+
+/// The JSON body delivered to every webhook.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertEvent {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: &'static str,
+    pub rule: AlertRule,
+    pub operator_id: String,
+    pub occurred_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<AlertDetails>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertDetails {
+    pub hhi_strategy: Option<f64>,
+    pub zero_share: bool,
+    pub last_slash_at: Option<i64>,
+    pub tvl_total_atomic: String,
+    pub tvl_total_decimal: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+}
+
+/// A delivery of one event to one URL, queued in `webhook_deliveries`.
+#[derive(Debug, Clone)]
+pub struct NewWebhookDelivery {
+    pub event_id: String,
+    pub url: String,
+    pub event_type: &'static str,
+    pub rule: AlertRule,
+    pub operator_id: String,
+    pub payload: serde_json::Value,
+}
+
+/// A queued delivery that is due for its next attempt.
+#[derive(Debug, Clone)]
+pub struct PendingWebhookDelivery {
+    pub delivery_id: i64,
+    pub event_id: String,
+    pub url: String,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+}
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+
+pub const AVS_REGISTERED: i32 = 1;
+
+/* --- AVS: variables --- */
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AvsPageVars {
+    pub first: i32,
+    pub cursor: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AvsOperatorsVars {
+    pub id: String,
+    pub first: i32,
+    /// Operator id after which registrations are read; empty for the first.
+    pub cursor: String,
+}
+
+/* --- AVS: response --- */
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AvsPageData {
+    pub avss: Vec<AvsDto>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvsOperatorsData {
+    pub avs: Option<AvsDto>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AvsDto {
+    pub id: String,
+    pub operator_count: i32,
+    pub last_update_block_timestamp: String,
+    #[serde(default)]
+    pub registrations: Vec<AvsRegistrationDto>,
+    #[serde(default)]
+    pub registrations_incomplete: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AvsRegistrationDto {
+    pub status: i32,
+    pub last_update_block_timestamp: String,
+    pub operator: AvsOperatorRefDto,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AvsOperatorRefDto {
+    pub id: String,
+}
+
+/* --- AVS: uniform --- */
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UniformAvs {
+    pub avs_id: String,
+    pub operator_count: i32,
+    pub last_update_block_ts: i64,
+    pub registrations: Vec<AvsRegistration>,
+    #[serde(default)]
+    pub registrations_incomplete: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvsRegistration {
+    pub operator_id: String,
+    pub status: i32,
+    pub last_update_block_ts: i64,
+}
+
+impl From<&AvsDto> for UniformAvs {
+    fn from(a: &AvsDto) -> Self {
+        Self {
+            avs_id: a.id.clone(),
+            operator_count: a.operator_count,
+            last_update_block_ts: a.last_update_block_timestamp.parse::<i64>().unwrap_or(0),
+            registrations: a
+                .registrations
+                .iter()
+                .map(|r| AvsRegistration {
+                    operator_id: r.operator.id.clone(),
+                    status: r.status,
+                    last_update_block_ts: r.last_update_block_timestamp.parse::<i64>().unwrap_or(0),
+                })
+                .collect(),
+            registrations_incomplete: a.registrations_incomplete,
+        }
+    }
+}
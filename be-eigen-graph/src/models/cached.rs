@@ -3,14 +3,44 @@ use serde::Serialize;
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum DataSource {
+    Memory,
     Redis,
     Subgraph,
     Db,
+    Fixture,
 }
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Cached<T> {
     pub source: DataSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cached_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age_seconds: Option<i64>,
+    pub stale: bool,
     pub data: T,
 }
+
+impl<T> Cached<T> {
+    /// Data that was just read from its origin rather than from a cache.
+    pub fn fresh(source: DataSource, data: T) -> Self {
+        Self {
+            source,
+            cached_at: None,
+            age_seconds: None,
+            stale: false,
+            data,
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Cached<U> {
+        Cached {
+            source: self.source,
+            cached_at: self.cached_at,
+            age_seconds: self.age_seconds,
+            stale: self.stale,
+            data: f(self.data),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IngestStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl IngestStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IngestStatus::Running => "running",
+            IngestStatus::Succeeded => "succeeded",
+            IngestStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "running" => IngestStatus::Running,
+            "succeeded" => IngestStatus::Succeeded,
+            _ => IngestStatus::Failed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct IngestStats {
+    pub pages: i32,
+    pub rows: i32,
+    pub errors: i32,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestRun {
+    pub run_id: i64,
+    pub kind: String,
+    pub status: IngestStatus,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+    pub pages: i32,
+    pub rows: i32,
+    pub errors: i32,
+    pub last_error: Option<String>,
+}
@@ -0,0 +1,13 @@
+use crate::payloads::operators::{AggregatesResponse, TokenSlice};
+use std::collections::BTreeMap;
+
+/// One published set of aggregates; `seq` increases with every publish.
+#[derive(Debug)]
+pub struct LiveFrame {
+    pub seq: u64,
+    pub published_at: i64,
+    pub block: Option<i64>,
+    pub aggregates: AggregatesResponse,
+    /// The token slices keyed by token address, for the `token` filter.
+    pub by_token_id: BTreeMap<String, TokenSlice>,
+}
@@ -1,7 +1,14 @@
+pub mod alerts;
+pub mod avs;
 pub mod cached;
 pub mod ids;
+pub mod ingest;
+pub mod live;
 pub mod operator;
+pub mod operator_detail;
+pub mod operator_history;
 pub mod operators_aggr;
+pub mod operators_diff;
 pub mod operators_snapshot;
 pub mod strategy;
 pub mod subgraph;
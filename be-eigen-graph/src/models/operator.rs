@@ -10,6 +10,7 @@ pub struct OperatorRiskRow {
     pub strategy_count: u32,
     pub slashing_count: u32,
     pub last_slash_at: Option<BlockTimestamp>,
+    pub last_update_block_ts: BlockTimestamp,
     pub hhi: f64,
     pub tvl_by_token: Vec<TvlByToken>,
     pub positions: Vec<OperatorStrategyPosition>,
@@ -0,0 +1,18 @@
+use super::operators_aggr::UniformOperator;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlashingEvent {
+    pub slashing_id: String,
+    pub block_number: Option<i64>,
+    pub block_timestamp: i64,
+    pub transaction_hash: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OperatorDetail {
+    pub operator: UniformOperator,
+    pub slashings: Vec<SlashingEvent>,
+    pub slashings_incomplete: bool,
+}
@@ -0,0 +1,54 @@
+use serde::Serialize;
+
+/// One operator's metrics as captured by a single ingest run.
+#[derive(Debug, Clone)]
+pub struct OperatorHistoryRow {
+    pub operator_id: String,
+    pub avs_count: i32,
+    pub strategy_count: i32,
+    pub slashing_count: i32,
+    pub tvl_total_atomic: String,
+    pub hhi: Option<f64>,
+    pub last_update_block_ts: i64,
+    pub positions_incomplete: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct StrategyHistoryRow {
+    pub operator_id: String,
+    pub strategy_id: String,
+    pub token_id: String,
+    pub token_symbol: String,
+    pub token_decimals: i32,
+    pub total_shares: String,
+    pub exchange_rate: String,
+    pub tvl_atomic: String,
+}
+
+/// The last sample captured inside the bucket starting at `bucket_ts`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorHistoryPoint {
+    pub bucket_ts: i64,
+    pub captured_at: i64,
+    pub avs_count: i32,
+    pub strategy_count: i32,
+    pub slashing_count: i32,
+    pub tvl_total_atomic: String,
+    pub hhi: Option<f64>,
+    pub last_update_block_ts: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyHistoryPoint {
+    pub bucket_ts: i64,
+    pub captured_at: i64,
+    pub strategy_id: String,
+    pub token_id: String,
+    pub token_symbol: String,
+    pub token_decimals: i32,
+    pub total_shares: String,
+    pub exchange_rate: String,
+    pub tvl_atomic: String,
+}
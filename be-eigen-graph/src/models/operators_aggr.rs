@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -12,6 +13,11 @@ pub struct UniformPage {
 pub struct PageMeta {
     pub first: i32,
     pub skip: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Block the page was read at, when the source knows it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +30,8 @@ pub struct UniformOperator {
     pub last_slash_at: Option<i64>,
     pub last_update_block_ts: i64,
     pub positions: Vec<UniformPosition>,
+    #[serde(default)]
+    pub positions_incomplete: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,13 +77,21 @@ pub struct OperatorAggregate {
     pub last_slash_at: Option<i64>,
     pub last_update_block_ts: i64,
 
+    // summed across tokens at NORMALIZED_DECIMALS
     pub tvl_total_atomic: String,
+    pub tvl_total_decimal: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tvl_usd: Option<f64>,
     pub nonzero_strategy_count: i32,
 
     pub strategy_breakdown: Vec<StrategySlice>,
-    pub top_strategy_share: f64,
-    pub hhi_strategy: f64,
+    // by amount for single-token operators, by USD once fully priced, else None
+    pub top_strategy_share: Option<f64>,
+    pub hhi_strategy: Option<f64>,
+    #[serde(default)]
+    pub concentration: Option<ConcentrationMetrics>,
     pub zero_share_flag: bool,
+    pub positions_incomplete: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -83,11 +99,55 @@ pub struct OperatorAggregate {
 pub struct StrategySlice {
     pub strategy_id: String,
     pub tvl_atomic: String,
-    pub share: f64, // 0..1
+    pub tvl_decimal: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tvl_usd: Option<f64>,
+    pub share: Option<f64>, // 0..1
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcentrationMetrics {
+    pub count: usize,
+    pub hhi: f64,
+    pub gini: f64,
+    pub entropy: f64,
+    pub top_k: usize,
+    pub top_k_share: f64,
+    pub nakamoto: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct StrategyAggregate {
+    pub strategy_id: String,
+    pub token_id: String,
+    pub token_symbol: String,
+    pub token_decimals: i32,
+    pub exchange_rate: String,
+
+    pub tvl_total_atomic: String,
+    pub tvl_total_decimal: String,
+    pub operator_count: i32,
+    pub nonzero_operator_count: i32,
+
+    pub operator_breakdown: Vec<OperatorSlice>,
+    pub top_operator_share: f64,
+    pub hhi_operator: f64,
+    pub concentration: ConcentrationMetrics,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorSlice {
+    pub operator_id: String,
+    pub tvl_atomic: String,
+    pub tvl_decimal: String,
+    pub share: f64, // 0..1
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TableRow {
     pub operator_id: String,
     pub avs_count: i32,
@@ -96,15 +156,24 @@ pub struct TableRow {
     pub last_slash_at: Option<i64>,
     pub last_update_block_ts: i64,
     pub tvl_total_atomic: String,
-    pub hhi_strategy: f64,
+    pub tvl_total_decimal: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tvl_usd: Option<f64>,
+    pub hhi_strategy: Option<f64>,
+    #[serde(default)]
+    pub concentration: Option<ConcentrationMetrics>,
     pub nonzero_strategy_count: i32,
+    pub positions_incomplete: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BarItem {
     pub operator_id: String,
     pub tvl_total_atomic: String,
+    pub tvl_total_decimal: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tvl_usd: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -122,10 +191,39 @@ pub struct GraphEdge {
     pub weight_atomic: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Outliers {
     pub high_concentration: Vec<String>,
     pub zero_share: Vec<String>,
     pub recent_slashes: Vec<String>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkSummary {
+    pub operator_count: usize,
+    pub strategy_count: usize,
+    /// `usd` when every position is priced and the network-wide metrics are
+    /// weighted by USD value; `perToken` when only `by_token` is meaningful.
+    pub weighting: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tvl_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operators: Option<ConcentrationMetrics>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategies: Option<ConcentrationMetrics>,
+    /// The same measures within each token, keyed by symbol and weighted by
+    /// the token amount.
+    pub by_token: BTreeMap<String, TokenConcentration>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenConcentration {
+    pub symbol: String,
+    pub tvl_total_atomic: String,
+    pub tvl_total_decimal: String,
+    pub operators: ConcentrationMetrics,
+    pub strategies: ConcentrationMetrics,
+}
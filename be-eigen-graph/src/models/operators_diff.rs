@@ -0,0 +1,86 @@
+use crate::models::operators_aggr::TableRow;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountDelta {
+    pub from: i32,
+    pub to: i32,
+    pub delta: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RatioDelta {
+    pub from: f64,
+    pub to: f64,
+    pub delta: f64,
+}
+
+/// Atomic amounts are unsigned; `delta_atomic` carries a leading `-` when the
+/// amount shrank.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AmountDelta {
+    pub from_atomic: String,
+    pub to_atomic: String,
+    pub delta_atomic: String,
+    pub delta_decimal: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsdDelta {
+    pub from: f64,
+    pub to: f64,
+    pub delta: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyDelta {
+    pub strategy_id: String,
+    pub token_symbol: String,
+    pub tvl: AmountDelta,
+}
+
+/// Changes of one operator present at both points, on the
+/// `OperatorAggregate` fields.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorDelta {
+    pub operator_id: String,
+    pub avs_count: CountDelta,
+    pub strategy_count: CountDelta,
+    pub slashing_count: CountDelta,
+    pub nonzero_strategy_count: CountDelta,
+    pub last_slash_at: Option<i64>,
+    pub tvl: AmountDelta,
+    pub hhi_strategy: Option<RatioDelta>,
+    pub top_strategy_share: Option<RatioDelta>,
+    pub strategies: Vec<StrategyDelta>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Mover {
+    pub operator_id: String,
+    pub tvl: AmountDelta,
+    /// Set when movers are ranked by USD value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tvl_usd: Option<UsdDelta>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorsDiff {
+    pub added: Vec<TableRow>,
+    pub removed: Vec<TableRow>,
+    pub changed: Vec<OperatorDelta>,
+    /// Operators whose slashing count grew.
+    pub slashed: Vec<String>,
+    /// Largest absolute TVL changes, biggest first. Across tokens they are
+    /// ranked by USD value and only operators priced at both points qualify;
+    /// within one token they are ranked by amount.
+    pub movers: Vec<Mover>,
+}
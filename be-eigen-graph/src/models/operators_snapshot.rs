@@ -10,6 +10,59 @@ pub struct OperatorsSnapshotVars {
     pub order_by: OperatorOrderBy,
     pub order_direction: OrderDirection,
     pub has_slashing: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<BlockHeight>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorsSnapshotCursorVars {
+    pub first: i32,
+    pub cursor: String,
+    pub has_slashing: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<BlockHeight>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorStrategiesVars {
+    pub id: String,
+    pub first: i32,
+    pub cursor: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<BlockHeight>,
+}
+
+/// The Graph's `Block_height` input; an omitted block means the indexed head.
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct BlockHeight {
+    pub number: i64,
+}
+
+/// A point in time requested by `asOfBlock` / `asOfTimestamp`.
+#[derive(Debug, Clone, Copy)]
+pub enum AsOf {
+    Block(i64),
+    Timestamp(i64),
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorDetailVars {
+    pub id: String,
+    pub first: i32,
+}
+
+/// Slashings come newest first with ties in id order, so a page resumes
+/// strictly after the `(blockTimestamp, id)` of the last one read.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorSlashingsVars {
+    pub id: String,
+    pub first: i32,
+    pub before_ts: String,
+    pub after_id: String,
 }
 
 #[derive(Serialize, Debug)]
@@ -36,6 +89,36 @@ pub enum OrderDirection {
 #[serde(rename_all = "camelCase")]
 pub struct OperatorsSnapshotData {
     pub operators: Vec<OperatorDto>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    #[serde(default, alias = "_meta", skip_serializing_if = "Option::is_none")]
+    pub meta: Option<SubgraphMeta>,
+}
+
+/// `_meta { block { number timestamp } }`: the block a response was served at.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct SubgraphMeta {
+    pub block: SubgraphBlock,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct SubgraphBlock {
+    pub number: i64,
+    #[serde(default)]
+    pub timestamp: Option<i64>,
+}
+
+impl OperatorsSnapshotData {
+    pub fn block_number(&self) -> Option<i64> {
+        self.meta.as_ref().map(|m| m.block.number)
+    }
+
+    pub fn max_last_update_ts(&self) -> Option<i64> {
+        self.operators
+            .iter()
+            .filter_map(|o| o.last_update_block_timestamp.parse::<i64>().ok())
+            .max()
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -50,12 +133,62 @@ pub struct OperatorDto {
     pub slashings: Vec<OperatorSlashingDto>,
     #[serde(default)]
     pub strategies: Vec<OperatorStrategyLinkDto>,
+    #[serde(default)]
+    pub strategies_incomplete: bool,
+    #[serde(default)]
+    pub slashings_incomplete: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorStrategiesData {
+    pub operator: Option<OperatorStrategiesDto>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorStrategiesDto {
+    #[serde(default)]
+    pub strategies: Vec<OperatorStrategyPageLinkDto>,
+}
+
+/// A strategy link with the entity id the follow-up pages are keyed on.
+#[derive(Deserialize)]
+pub struct OperatorStrategyPageLinkDto {
+    pub id: String,
+    #[serde(flatten)]
+    pub link: OperatorStrategyLinkDto,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorDetailData {
+    pub operator: Option<OperatorDto>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorSlashingsData {
+    pub operator: Option<OperatorSlashingsDto>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorSlashingsDto {
+    #[serde(default)]
+    pub slashings: Vec<OperatorSlashingDto>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OperatorSlashingDto {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<String>,
     pub block_timestamp: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
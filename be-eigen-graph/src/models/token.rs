@@ -12,4 +12,5 @@ pub struct AtomicAmount(pub String);
 pub struct TvlByToken {
     pub token: TokenRef,
     pub amount_atomic: AtomicAmount,
+    pub amount_decimal: String,
 }
@@ -0,0 +1,64 @@
+use crate::payloads::operators::TvlView;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvsListQuery {
+    pub source: Option<String>,
+    pub first: Option<i32>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvsDetailQuery {
+    pub source: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvsListResponse {
+    pub meta: AvsListMeta,
+    pub items: Vec<AvsView>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvsListMeta {
+    pub source: String,
+    pub first: i32,
+    pub count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvsView {
+    pub avs_id: String,
+    pub operator_count: i32,
+    pub registered_operator_count: usize,
+    pub last_update_block_ts: i64,
+    pub registrations_incomplete: bool,
+    pub tvl_by_token: Vec<TvlView>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvsDetailView {
+    pub source: String,
+    #[serde(flatten)]
+    pub avs: AvsView,
+    pub operators: Vec<AvsOperatorView>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvsOperatorView {
+    pub operator_id: String,
+    pub registered: bool,
+    pub last_update_block_ts: i64,
+    pub valued: bool,
+    pub tvl_total_atomic: String,
+    pub hhi: Option<f64>,
+}
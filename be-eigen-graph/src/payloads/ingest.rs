@@ -0,0 +1,9 @@
+use crate::models::ingest::IngestRun;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestStatusView {
+    pub last_success: Option<IngestRun>,
+    pub latest: Option<IngestRun>,
+}
@@ -1 +1,6 @@
+pub mod admin;
+pub mod avs;
+pub mod ingest;
 pub mod operators;
+pub mod strategies;
+pub mod tokens;
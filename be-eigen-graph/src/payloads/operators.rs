@@ -1,5 +1,8 @@
 use crate::models::operator::OperatorRiskRow;
+use crate::models::operator_detail::SlashingEvent;
+use crate::models::operator_history::OperatorHistoryPoint;
 use crate::models::operators_aggr::{BarItem, GraphEdge, Outliers, TableRow};
+use crate::models::operators_diff::OperatorsDiff;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
@@ -11,6 +14,43 @@ pub struct SnapshotQuery {
     pub(crate) has_slashing: Option<i32>,
     pub(crate) order_by: Option<String>,
     pub(crate) order_direction: Option<String>,
+    pub(crate) after: Option<String>,
+    pub(crate) as_of_block: Option<i64>,
+    pub(crate) as_of_timestamp: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorRiskQuery {
+    pub first: Option<i32>,
+    pub skip: Option<i32>,
+    pub has_slashing: Option<i32>,
+    pub min_avs: Option<u32>,
+    pub concentration_max: Option<f64>,
+    pub token: Option<String>,
+    pub min_tvl_atomic: Option<String>,
+    pub order_by: Option<String>,
+    pub order_direction: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorRiskResponse {
+    pub meta: OperatorRiskMeta,
+    pub items: Vec<OperatorRiskItemView>,
+    pub next_skip: i32,
+    pub has_more: bool,
+}
+
+/// Filters and ordering run over the one subgraph page at `skip..skip+first`,
+/// not the whole operator set; `scope` is always `"page"` to say so.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorRiskMeta {
+    pub scope: String,
+    pub first: i32,
+    pub skip: i32,
+    pub count: usize,
 }
 
 #[derive(Serialize)]
@@ -21,6 +61,7 @@ pub struct OperatorRiskItemView {
     pub strategy_count: u32,
     pub slashing_count: u32,
     pub last_slash_at: Option<i64>,
+    pub last_update_block_ts: i64,
     pub hhi: f64,
     pub tvl: Vec<TvlView>,
 }
@@ -31,6 +72,7 @@ pub struct TvlView {
     pub token: String,
     pub symbol: String,
     pub amount_atomic: String,
+    pub amount_decimal: String,
 }
 
 impl From<OperatorRiskRow> for OperatorRiskItemView {
@@ -41,6 +83,7 @@ impl From<OperatorRiskRow> for OperatorRiskItemView {
             strategy_count: r.strategy_count,
             slashing_count: r.slashing_count,
             last_slash_at: r.last_slash_at.map(|t| t.0),
+            last_update_block_ts: r.last_update_block_ts.0,
             hhi: r.hhi,
             tvl: r
                 .tvl_by_token
@@ -49,6 +92,7 @@ impl From<OperatorRiskRow> for OperatorRiskItemView {
                     token: t.token.id.0,
                     symbol: t.token.symbol,
                     amount_atomic: t.amount_atomic.0,
+                    amount_decimal: t.amount_decimal,
                 })
                 .collect(),
         }
@@ -65,9 +109,12 @@ pub struct AggregatesQuery {
     pub hhi_threshold: Option<f64>,
     pub min_tvl_atomic: Option<String>,
     pub operator_id: Option<String>,
+    pub after: Option<String>,
+    pub as_of_block: Option<i64>,
+    pub as_of_timestamp: Option<i64>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AggregatesResponse {
     pub meta: AggregatesMeta,
@@ -79,16 +126,26 @@ pub struct AggregatesResponse {
     pub by_token: BTreeMap<String, TokenSlice>,
 }
 
-#[derive(Debug, Serialize)]
+impl AggregatesResponse {
+    pub fn max_last_update_ts(&self) -> Option<i64> {
+        self.table.iter().map(|r| r.last_update_block_ts).max()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AggregatesMeta {
     pub source: String,
     pub first: i32,
     pub skip: i32,
     pub count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block: Option<i64>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenSlice {
     pub meta: AggregatesMeta,
@@ -98,3 +155,177 @@ pub struct TokenSlice {
     pub graph: Vec<GraphEdge>,
     pub outliers: Outliers,
 }
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorDetailQuery {
+    pub source: Option<String>,
+    pub hhi_threshold: Option<f64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorDetailView {
+    pub source: String,
+    pub operator_id: String,
+    pub avs_count: i32,
+    pub strategy_count: i32,
+    pub slashing_count: i32,
+    pub last_slash_at: Option<i64>,
+    pub last_update_block_ts: i64,
+    pub tvl_total_atomic: String,
+    pub tvl_total_decimal: String,
+    pub hhi: Option<f64>,
+    pub top_strategy_share: Option<f64>,
+    pub positions_incomplete: bool,
+    pub positions: Vec<PositionView>,
+    pub tvl_by_token: Vec<TvlView>,
+    pub slashings: Vec<SlashingEvent>,
+    pub slashings_incomplete: bool,
+    pub outliers: OperatorOutlierFlags,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionView {
+    pub strategy_id: String,
+    pub token_id: String,
+    pub token_symbol: String,
+    pub token_decimals: i32,
+    pub total_shares: String,
+    pub exchange_rate: String,
+    pub tvl_atomic: String,
+    pub tvl_decimal: String,
+    pub share: Option<f64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorOutlierFlags {
+    pub high_concentration: bool,
+    pub zero_share: bool,
+    pub recent_slash: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorHistoryQuery {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub interval: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorHistoryResponse {
+    pub operator_id: String,
+    pub from: i64,
+    pub to: i64,
+    pub interval_secs: i64,
+    pub points: Vec<OperatorHistoryPoint>,
+    pub strategies: Vec<StrategyHistorySeries>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyHistorySeries {
+    pub strategy_id: String,
+    pub token_id: String,
+    pub token_symbol: String,
+    pub token_decimals: i32,
+    pub points: Vec<StrategyHistoryPointView>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyHistoryPointView {
+    pub bucket_ts: i64,
+    pub captured_at: i64,
+    pub total_shares: String,
+    pub exchange_rate: String,
+    pub tvl_atomic: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorsDiffQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub top_n: Option<usize>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorsDiffResponse {
+    pub from: DiffSideMeta,
+    pub to: DiffSideMeta,
+    #[serde(flatten)]
+    pub diff: OperatorsDiff,
+    pub by_token: BTreeMap<String, OperatorsDiff>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffSideMeta {
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub captured_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<i64>,
+    pub count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveQuery {
+    pub token: Option<String>,
+    pub operator_id: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LiveMessage {
+    Snapshot(LiveSnapshot),
+    Patch(LivePatch),
+}
+
+impl LiveMessage {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LiveMessage::Snapshot(_) => "snapshot",
+            LiveMessage::Patch(_) => "patch",
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveSnapshot {
+    pub seq: u64,
+    pub published_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<i64>,
+    pub table: Vec<TableRow>,
+    pub bar: Vec<BarItem>,
+    pub outliers: Outliers,
+}
+
+/// Changes since the previous message on the same connection: table rows
+/// are upserted or removed by `operatorId`; `bar` and `outliers` are only
+/// present when they changed and then replace the previous value.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LivePatch {
+    pub seq: u64,
+    pub published_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<i64>,
+    pub upserted: Vec<TableRow>,
+    pub removed: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bar: Option<Vec<BarItem>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outliers: Option<Outliers>,
+}
@@ -0,0 +1,70 @@
+use crate::models::operators_aggr::{ConcentrationMetrics, OperatorSlice, StrategyAggregate};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategiesQuery {
+    pub hhi_threshold: Option<f64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategiesResponse {
+    pub meta: StrategiesMeta,
+    pub items: Vec<StrategyView>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategiesMeta {
+    pub source: String,
+    pub count: usize,
+    pub operator_count: usize,
+    pub hhi_threshold: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyView {
+    pub strategy_id: String,
+    pub token_id: String,
+    pub token_symbol: String,
+    pub token_decimals: i32,
+    pub exchange_rate: String,
+    pub tvl_total_atomic: String,
+    pub tvl_total_decimal: String,
+    pub operator_count: i32,
+    pub nonzero_operator_count: i32,
+    pub top_operator_share: f64,
+    pub hhi: f64,
+    pub concentration: ConcentrationMetrics,
+    pub high_concentration: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyDetailView {
+    #[serde(flatten)]
+    pub strategy: StrategyView,
+    pub operators: Vec<OperatorSlice>,
+}
+
+impl StrategyView {
+    pub fn from_aggregate(a: &StrategyAggregate, hhi_threshold: f64) -> Self {
+        Self {
+            strategy_id: a.strategy_id.clone(),
+            token_id: a.token_id.clone(),
+            token_symbol: a.token_symbol.clone(),
+            token_decimals: a.token_decimals,
+            exchange_rate: a.exchange_rate.clone(),
+            tvl_total_atomic: a.tvl_total_atomic.clone(),
+            tvl_total_decimal: a.tvl_total_decimal.clone(),
+            operator_count: a.operator_count,
+            nonzero_operator_count: a.nonzero_operator_count,
+            top_operator_share: a.top_operator_share,
+            hhi: a.hhi_operator,
+            concentration: a.concentration.clone(),
+            high_concentration: a.hhi_operator >= hhi_threshold,
+        }
+    }
+}
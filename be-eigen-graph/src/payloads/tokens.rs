@@ -0,0 +1,30 @@
+use crate::models::operators_aggr::ConcentrationMetrics;
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokensResponse {
+    pub meta: TokensMeta,
+    pub items: Vec<TokenView>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokensMeta {
+    pub source: String,
+    pub count: usize,
+    pub operator_count: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenView {
+    pub token_id: String,
+    pub symbol: String,
+    pub decimals: i32,
+    pub tvl_total_atomic: String,
+    pub tvl_total_decimal: String,
+    pub operator_count: usize,
+    pub concentration: ConcentrationMetrics,
+    pub strategies: Vec<String>,
+}
@@ -0,0 +1,161 @@
+use crate::metrics::DbTimer;
+use crate::models::alerts::{
+    AlertRule, DeliveryStatus, NewWebhookDelivery, PendingWebhookDelivery,
+};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+pub async fn select_alert_state(
+    pool: &PgPool,
+) -> Result<BTreeSet<(AlertRule, String)>, sqlx::Error> {
+    let _t = DbTimer::new("select_alert_state");
+    let rows = sqlx::query("SELECT rule, operator_id FROM alert_state")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|r| {
+            let rule = AlertRule::parse(r.get::<&str, _>("rule"))?;
+            Some((rule, r.get::<String, _>("operator_id")))
+        })
+        .collect())
+}
+
+/// Moves the remembered alert state forward and queues the webhook
+/// deliveries announcing the change in the same transaction, so a crash can
+/// neither lose nor duplicate a notification.
+pub async fn apply_alert_transitions(
+    pool: &PgPool,
+    entered: &[(AlertRule, String)],
+    left: &[(AlertRule, String)],
+    now_ts: i64,
+    deliveries: &[NewWebhookDelivery],
+) -> Result<(), sqlx::Error> {
+    let mut tx: Transaction<Postgres> = pool.begin().await?;
+
+    for (rule, operator_id) in entered {
+        let _t = DbTimer::new("insert_alert_state");
+        sqlx::query(
+            r#"
+                INSERT INTO alert_state (rule, operator_id, since)
+                VALUES ($1,$2,$3)
+                ON CONFLICT (rule, operator_id) DO NOTHING
+            "#,
+        )
+        .bind(rule.as_str())
+        .bind(operator_id)
+        .bind(now_ts)
+        .execute(tx.as_mut())
+        .await?;
+    }
+
+    for (rule, operator_id) in left {
+        let _t = DbTimer::new("delete_alert_state");
+        sqlx::query("DELETE FROM alert_state WHERE rule = $1 AND operator_id = $2")
+            .bind(rule.as_str())
+            .bind(operator_id)
+            .execute(tx.as_mut())
+            .await?;
+    }
+
+    for d in deliveries {
+        let _t = DbTimer::new("insert_webhook_delivery");
+        sqlx::query(
+            r#"
+                INSERT INTO webhook_deliveries
+                        (event_id, url, event_type, rule, operator_id, payload, status)
+                VALUES ($1,$2,$3,$4,$5,$6,$7)
+            "#,
+        )
+        .bind(&d.event_id)
+        .bind(&d.url)
+        .bind(d.event_type)
+        .bind(d.rule.as_str())
+        .bind(&d.operator_id)
+        .bind(&d.payload)
+        .bind(DeliveryStatus::Pending.as_str())
+        .execute(tx.as_mut())
+        .await?;
+    }
+
+    tx.commit().await
+}
+
+/// The oldest pending delivery of each URL, for the URLs where it is due. A
+/// URL whose head is backing off yields nothing, so later deliveries to it
+/// never overtake the one being retried.
+pub async fn select_due_webhook_deliveries(
+    pool: &PgPool,
+    limit: i64,
+) -> Result<Vec<PendingWebhookDelivery>, sqlx::Error> {
+    let _t = DbTimer::new("select_due_webhook_deliveries");
+    let rows = sqlx::query(
+        r#"
+            SELECT delivery_id, event_id, url, event_type, payload, attempts
+            FROM (
+                SELECT DISTINCT ON (url)
+                       delivery_id, event_id, url, event_type, payload, attempts, next_attempt_at
+                FROM webhook_deliveries
+                WHERE status = $1
+                ORDER BY url, delivery_id ASC
+            ) heads
+            WHERE next_attempt_at <= now()
+            ORDER BY delivery_id ASC
+            LIMIT $2
+        "#,
+    )
+    .bind(DeliveryStatus::Pending.as_str())
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| PendingWebhookDelivery {
+            delivery_id: r.get("delivery_id"),
+            event_id: r.get("event_id"),
+            url: r.get("url"),
+            event_type: r.get("event_type"),
+            payload: r.get("payload"),
+            attempts: r.get("attempts"),
+        })
+        .collect())
+}
+
+/// Records one attempt. A delivery left `Pending` is retried after
+/// `retry_in`; any other status is final.
+pub async fn record_webhook_attempt(
+    pool: &PgPool,
+    delivery_id: i64,
+    status: DeliveryStatus,
+    attempts: i32,
+    response_status: Option<i32>,
+    last_error: Option<&str>,
+    retry_in: Duration,
+) -> Result<(), sqlx::Error> {
+    let _t = DbTimer::new("record_webhook_attempt");
+    sqlx::query(
+        r#"
+            UPDATE webhook_deliveries
+               SET status = $2,
+                   attempts = $3,
+                   response_status = $4,
+                   last_error = $5,
+                   next_attempt_at = now() + make_interval(secs => $6),
+                   finished_at = CASE WHEN $2 = $7 THEN NULL ELSE now() END
+             WHERE delivery_id = $1
+        "#,
+    )
+    .bind(delivery_id)
+    .bind(status.as_str())
+    .bind(attempts)
+    .bind(response_status)
+    .bind(last_error)
+    .bind(retry_in.as_secs_f64())
+    .bind(DeliveryStatus::Pending.as_str())
+    .execute(pool)
+    .await
+    .map(|_| ())
+}
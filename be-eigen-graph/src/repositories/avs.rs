@@ -0,0 +1,141 @@
+use crate::metrics::DbTimer;
+use crate::models::avs::{AvsRegistration, UniformAvs};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::collections::BTreeMap;
+
+pub async fn upsert_avs_page(pool: &PgPool, avss: &[UniformAvs]) -> Result<(), sqlx::Error> {
+    let mut tx: Transaction<Postgres> = pool.begin().await?;
+
+    for avs in avss {
+        let _t = DbTimer::new("upsert_avs");
+        sqlx::query(
+            r#"
+                INSERT INTO avs
+                        (avs_id, operator_count, last_update_block_ts, registrations_incomplete)
+                VALUES ($1,$2,$3,$4)
+                ON CONFLICT (avs_id) DO UPDATE
+                    SET operator_count = EXCLUDED.operator_count,
+                        last_update_block_ts = EXCLUDED.last_update_block_ts,
+                        registrations_incomplete = EXCLUDED.registrations_incomplete
+                "#,
+        )
+        .bind(&avs.avs_id)
+        .bind(avs.operator_count)
+        .bind(avs.last_update_block_ts)
+        .bind(avs.registrations_incomplete)
+        .execute(tx.as_mut())
+        .await?;
+
+        let _t2 = DbTimer::new("delete_avs_registrations");
+        sqlx::query("DELETE FROM operator_avs_registration WHERE avs_id = $1")
+            .bind(&avs.avs_id)
+            .execute(tx.as_mut())
+            .await?;
+
+        for reg in &avs.registrations {
+            let _t3 = DbTimer::new("insert_avs_registration");
+            sqlx::query(
+                r#"
+                    INSERT INTO operator_avs_registration
+                        (avs_id, operator_id, status, last_update_block_ts)
+                    VALUES ($1,$2,$3,$4)
+                    ON CONFLICT (avs_id, operator_id) DO NOTHING
+                "#,
+            )
+            .bind(&avs.avs_id)
+            .bind(&reg.operator_id)
+            .bind(reg.status)
+            .bind(reg.last_update_block_ts)
+            .execute(tx.as_mut())
+            .await?;
+        }
+    }
+
+    tx.commit().await
+}
+
+pub async fn select_avs_after(
+    pool: &PgPool,
+    first: i32,
+    cursor: &str,
+) -> Result<Vec<UniformAvs>, sqlx::Error> {
+    let _t = DbTimer::new("select_avs_after");
+    let rows = sqlx::query(
+        r#"
+            SELECT avs_id, operator_count, last_update_block_ts, registrations_incomplete
+            FROM avs
+            WHERE avs_id > $2
+            ORDER BY avs_id ASC
+            LIMIT $1
+        "#,
+    )
+    .bind(first as i64)
+    .bind(cursor)
+    .fetch_all(pool)
+    .await?;
+
+    load_avs(pool, rows).await
+}
+
+pub async fn select_avs(pool: &PgPool, avs_id: &str) -> Result<Option<UniformAvs>, sqlx::Error> {
+    let _t = DbTimer::new("select_avs");
+    let rows = sqlx::query(
+        r#"
+            SELECT avs_id, operator_count, last_update_block_ts, registrations_incomplete
+            FROM avs
+            WHERE avs_id = $1
+        "#,
+    )
+    .bind(avs_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(load_avs(pool, rows).await?.into_iter().next())
+}
+
+async fn load_avs(pool: &PgPool, rows: Vec<PgRow>) -> Result<Vec<UniformAvs>, sqlx::Error> {
+    if rows.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let avs_ids: Vec<String> = rows.iter().map(|r| r.get::<String, _>("avs_id")).collect();
+    let avs_id_refs: Vec<&str> = avs_ids.iter().map(AsRef::as_ref).collect();
+
+    let _t = DbTimer::new("select_avs_registrations");
+    let reg_rows = sqlx::query(
+        r#"
+            SELECT avs_id, operator_id, status, last_update_block_ts
+            FROM operator_avs_registration
+            WHERE avs_id = ANY($1)
+            ORDER BY operator_id ASC
+        "#,
+    )
+    .bind(&avs_id_refs[..])
+    .fetch_all(pool)
+    .await?;
+
+    let mut reg_map: BTreeMap<String, Vec<AvsRegistration>> = BTreeMap::new();
+    for r in reg_rows {
+        let avs_id: String = r.get("avs_id");
+        reg_map.entry(avs_id).or_default().push(AvsRegistration {
+            operator_id: r.get("operator_id"),
+            status: r.get::<i32, _>("status"),
+            last_update_block_ts: r.get::<i64, _>("last_update_block_ts"),
+        });
+    }
+
+    Ok(rows
+        .into_iter()
+        .map(|r| {
+            let avs_id: String = r.get("avs_id");
+            UniformAvs {
+                avs_id: avs_id.clone(),
+                operator_count: r.get::<i32, _>("operator_count"),
+                last_update_block_ts: r.get::<i64, _>("last_update_block_ts"),
+                registrations: reg_map.remove(&avs_id).unwrap_or_default(),
+                registrations_incomplete: r.get::<bool, _>("registrations_incomplete"),
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,110 @@
+use crate::metrics::DbTimer;
+use crate::models::ingest::{IngestRun, IngestStats, IngestStatus};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+
+pub async fn start_ingest_run(pool: &PgPool, kind: &str) -> Result<i64, sqlx::Error> {
+    let _t = DbTimer::new("start_ingest_run");
+    let row = sqlx::query(
+        r#"
+            INSERT INTO ingest_runs (kind, status)
+            VALUES ($1, $2)
+            RETURNING run_id
+        "#,
+    )
+    .bind(kind)
+    .bind(IngestStatus::Running.as_str())
+    .fetch_one(pool)
+    .await?;
+    Ok(row.get::<i64, _>("run_id"))
+}
+
+pub async fn finish_ingest_run(
+    pool: &PgPool,
+    run_id: i64,
+    status: IngestStatus,
+    stats: &IngestStats,
+) -> Result<(), sqlx::Error> {
+    let _t = DbTimer::new("finish_ingest_run");
+    sqlx::query(
+        r#"
+            UPDATE ingest_runs
+               SET status = $2,
+                   finished_at = now(),
+                   pages = $3,
+                   rows = $4,
+                   errors = $5,
+                   last_error = $6
+             WHERE run_id = $1
+        "#,
+    )
+    .bind(run_id)
+    .bind(status.as_str())
+    .bind(stats.pages)
+    .bind(stats.rows)
+    .bind(stats.errors)
+    .bind(&stats.last_error)
+    .execute(pool)
+    .await
+    .map(|_| ())
+}
+
+/// Closes runs a previous process left `running` after it panicked or was
+/// stopped mid-run, so they read as failed instead of in progress forever.
+pub async fn fail_abandoned_ingest_runs(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let _t = DbTimer::new("fail_abandoned_ingest_runs");
+    sqlx::query(
+        r#"
+            UPDATE ingest_runs
+               SET status = $2,
+                   finished_at = now(),
+                   errors = errors + 1,
+                   last_error = 'abandoned: the process stopped before the run finished'
+             WHERE status = $1
+        "#,
+    )
+    .bind(IngestStatus::Running.as_str())
+    .bind(IngestStatus::Failed.as_str())
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected())
+}
+
+pub async fn last_ingest_run(
+    pool: &PgPool,
+    kind: &str,
+    status: Option<IngestStatus>,
+) -> Result<Option<IngestRun>, sqlx::Error> {
+    let _t = DbTimer::new("select_ingest_run");
+    let row = sqlx::query(
+        r#"
+            SELECT run_id, kind, status,
+                   EXTRACT(EPOCH FROM started_at)::BIGINT AS started_at,
+                   EXTRACT(EPOCH FROM finished_at)::BIGINT AS finished_at,
+                   pages, rows, errors, last_error
+            FROM ingest_runs
+            WHERE kind = $1 AND ($2::TEXT IS NULL OR status = $2)
+            ORDER BY started_at DESC
+            LIMIT 1
+        "#,
+    )
+    .bind(kind)
+    .bind(status.map(|s| s.as_str()))
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.as_ref().map(map_ingest_run))
+}
+
+fn map_ingest_run(r: &PgRow) -> IngestRun {
+    IngestRun {
+        run_id: r.get("run_id"),
+        kind: r.get("kind"),
+        status: IngestStatus::parse(r.get::<&str, _>("status")),
+        started_at: r.get("started_at"),
+        finished_at: r.get("finished_at"),
+        pages: r.get("pages"),
+        rows: r.get("rows"),
+        errors: r.get("errors"),
+        last_error: r.get("last_error"),
+    }
+}
@@ -1 +1,6 @@
+pub mod alerts;
+pub mod avs;
+pub mod ingest_runs;
+pub mod operator_history;
 pub mod operators;
+pub mod ticks;
@@ -0,0 +1,266 @@
+use crate::metrics::DbTimer;
+use crate::models::ingest::IngestStatus;
+use crate::models::operator_history::{
+    OperatorHistoryPoint, OperatorHistoryRow, StrategyHistoryPoint, StrategyHistoryRow,
+};
+use chrono::{DateTime, Datelike, NaiveDate};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+
+const HISTORY_TABLES: [&str; 2] = ["operator_history", "operator_strategy_history"];
+
+/// History tables are range-partitioned by month on `captured_at`; the
+/// partition covering `captured_at` is created the first time it is needed.
+pub async fn ensure_history_partitions(pool: &PgPool, captured_at: i64) -> Result<(), sqlx::Error> {
+    let Some((name, from, to)) = month_partition(captured_at) else {
+        return Err(sqlx::Error::Protocol(format!(
+            "captured_at out of range: {captured_at}"
+        )));
+    };
+
+    for table in HISTORY_TABLES {
+        let _t = DbTimer::new("ensure_history_partition");
+        let ddl = format!(
+            "CREATE TABLE IF NOT EXISTS {table}_{name} PARTITION OF {table} FOR VALUES FROM ({from}) TO ({to})"
+        );
+        sqlx::query(&ddl).execute(pool).await?;
+    }
+    Ok(())
+}
+
+pub async fn insert_operator_history(
+    pool: &PgPool,
+    run_id: i64,
+    captured_at: i64,
+    block_number: Option<i64>,
+    operators: &[OperatorHistoryRow],
+    strategies: &[StrategyHistoryRow],
+) -> Result<(), sqlx::Error> {
+    let mut tx: Transaction<Postgres> = pool.begin().await?;
+
+    for op in operators {
+        let _t = DbTimer::new("insert_operator_history");
+        sqlx::query(
+            r#"
+                INSERT INTO operator_history
+                        (operator_id, captured_at, avs_count, strategy_count, slashing_count, tvl_total_atomic, hhi, last_update_block_ts, block_number, positions_incomplete, run_id)
+                VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11)
+                ON CONFLICT (operator_id, captured_at) DO NOTHING
+                "#,
+        )
+            .bind(&op.operator_id)
+            .bind(captured_at)
+            .bind(op.avs_count)
+            .bind(op.strategy_count)
+            .bind(op.slashing_count)
+            .bind(&op.tvl_total_atomic)
+            .bind(op.hhi)
+            .bind(op.last_update_block_ts)
+            .bind(block_number)
+            .bind(op.positions_incomplete)
+            .bind(run_id)
+            .execute(tx.as_mut())
+            .await?;
+    }
+
+    for s in strategies {
+        let _t = DbTimer::new("insert_strategy_history");
+        sqlx::query(
+            r#"
+                INSERT INTO operator_strategy_history
+                        (operator_id, strategy_id, captured_at, token_id, token_symbol, token_decimals, total_shares, exchange_rate, tvl_atomic)
+                VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9)
+                ON CONFLICT (operator_id, strategy_id, captured_at) DO NOTHING
+                "#,
+        )
+            .bind(&s.operator_id)
+            .bind(&s.strategy_id)
+            .bind(captured_at)
+            .bind(&s.token_id)
+            .bind(&s.token_symbol)
+            .bind(s.token_decimals)
+            .bind(&s.total_shares)
+            .bind(&s.exchange_rate)
+            .bind(&s.tvl_atomic)
+            .execute(tx.as_mut())
+            .await?;
+    }
+
+    tx.commit().await
+}
+
+/// Samples in `[from, to)` downsampled to one point per `interval_secs`
+/// bucket; each bucket keeps its latest sample.
+pub async fn select_operator_history(
+    pool: &PgPool,
+    operator_id: &str,
+    from: i64,
+    to: i64,
+    interval_secs: i64,
+) -> Result<Vec<OperatorHistoryPoint>, sqlx::Error> {
+    let _t = DbTimer::new("select_operator_history");
+    let rows = sqlx::query(
+        r#"
+            SELECT DISTINCT ON (bucket_ts)
+                   (captured_at / $4) * $4 AS bucket_ts,
+                   captured_at, avs_count, strategy_count, slashing_count,
+                   tvl_total_atomic, hhi, last_update_block_ts
+            FROM operator_history
+            WHERE operator_id = $1 AND captured_at >= $2 AND captured_at < $3
+            ORDER BY bucket_ts ASC, captured_at DESC
+        "#,
+    )
+    .bind(operator_id)
+    .bind(from)
+    .bind(to)
+    .bind(interval_secs)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| OperatorHistoryPoint {
+            bucket_ts: r.get("bucket_ts"),
+            captured_at: r.get("captured_at"),
+            avs_count: r.get("avs_count"),
+            strategy_count: r.get("strategy_count"),
+            slashing_count: r.get("slashing_count"),
+            tvl_total_atomic: r.get("tvl_total_atomic"),
+            hhi: r.get("hhi"),
+            last_update_block_ts: r.get("last_update_block_ts"),
+        })
+        .collect())
+}
+
+pub async fn select_strategy_history(
+    pool: &PgPool,
+    operator_id: &str,
+    from: i64,
+    to: i64,
+    interval_secs: i64,
+) -> Result<Vec<StrategyHistoryPoint>, sqlx::Error> {
+    let _t = DbTimer::new("select_strategy_history");
+    let rows = sqlx::query(
+        r#"
+            SELECT DISTINCT ON (strategy_id, bucket_ts)
+                   strategy_id,
+                   (captured_at / $4) * $4 AS bucket_ts,
+                   captured_at, token_id, token_symbol, token_decimals,
+                   total_shares, exchange_rate, tvl_atomic
+            FROM operator_strategy_history
+            WHERE operator_id = $1 AND captured_at >= $2 AND captured_at < $3
+            ORDER BY strategy_id ASC, bucket_ts ASC, captured_at DESC
+        "#,
+    )
+    .bind(operator_id)
+    .bind(from)
+    .bind(to)
+    .bind(interval_secs)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| StrategyHistoryPoint {
+            bucket_ts: r.get("bucket_ts"),
+            captured_at: r.get("captured_at"),
+            strategy_id: r.get("strategy_id"),
+            token_id: r.get("token_id"),
+            token_symbol: r.get("token_symbol"),
+            token_decimals: r.get("token_decimals"),
+            total_shares: r.get("total_shares"),
+            exchange_rate: r.get("exchange_rate"),
+            tvl_atomic: r.get("tvl_atomic"),
+        })
+        .collect())
+}
+
+/// The block the most recent succeeded ingest at or before `ts` was read at.
+pub async fn select_block_at(pool: &PgPool, ts: i64) -> Result<Option<i64>, sqlx::Error> {
+    let _t = DbTimer::new("select_history_block_at");
+    let row = sqlx::query(
+        r#"
+            SELECT h.block_number
+            FROM operator_history h
+            JOIN ingest_runs r ON r.run_id = h.run_id AND r.status = $2
+            WHERE h.captured_at <= $1 AND h.block_number IS NOT NULL
+            ORDER BY h.captured_at DESC
+            LIMIT 1
+        "#,
+    )
+    .bind(ts)
+    .bind(IngestStatus::Succeeded.as_str())
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| r.get("block_number")))
+}
+
+/// When the last succeeded ingest run at or before `ts` sampled the
+/// operators.
+pub async fn select_succeeded_capture_at(
+    pool: &PgPool,
+    ts: i64,
+) -> Result<Option<i64>, sqlx::Error> {
+    let _t = DbTimer::new("select_history_capture_at");
+    let row = sqlx::query(
+        r#"
+            SELECT h.captured_at
+            FROM operator_history h
+            JOIN ingest_runs r ON r.run_id = h.run_id AND r.status = $2
+            WHERE h.captured_at <= $1
+            ORDER BY h.captured_at DESC
+            LIMIT 1
+        "#,
+    )
+    .bind(ts)
+    .bind(IngestStatus::Succeeded.as_str())
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| r.get("captured_at")))
+}
+
+/// `(suffix, from, to)` of the calendar month (UTC) containing `ts`.
+fn month_partition(ts: i64) -> Option<(String, i64, i64)> {
+    let date = DateTime::from_timestamp(ts, 0)?.date_naive();
+    let start = NaiveDate::from_ymd_opt(date.year(), date.month(), 1)?;
+    let end = if date.month() == 12 {
+        NaiveDate::from_ymd_opt(date.year() + 1, 1, 1)?
+    } else {
+        NaiveDate::from_ymd_opt(date.year(), date.month() + 1, 1)?
+    };
+    Some((
+        format!("y{:04}m{:02}", date.year(), date.month()),
+        start.and_hms_opt(0, 0, 0)?.and_utc().timestamp(),
+        end.and_hms_opt(0, 0, 0)?.and_utc().timestamp(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn december_rolls_into_next_year() {
+        // 2025-12-31T23:59:59Z
+        let (name, start, end) = month_partition(1_767_225_599).unwrap();
+        assert_eq!(name, "y2025m12");
+        assert_eq!(start, 1_764_547_200);
+        assert_eq!(end, 1_767_225_600);
+        assert_eq!(month_partition(end).unwrap().0, "y2026m01");
+    }
+
+    #[test]
+    fn leap_february_ends_on_march_first() {
+        // 2024-02-29T12:00:00Z
+        let (name, start, end) = month_partition(1_709_208_000).unwrap();
+        assert_eq!(name, "y2024m02");
+        assert_eq!(start, 1_706_745_600);
+        assert_eq!(end, 1_709_251_200);
+    }
+
+    #[test]
+    fn out_of_range_timestamp_has_no_partition() {
+        assert!(month_partition(i64::MAX).is_none());
+    }
+}
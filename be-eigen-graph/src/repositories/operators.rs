@@ -1,6 +1,8 @@
 use crate::metrics::DbTimer;
-use crate::models::operators_snapshot::OperatorsSnapshotData;
-use sqlx::{PgPool, Postgres, Transaction};
+use crate::models::operator_detail::SlashingEvent;
+use crate::models::operators_snapshot::{OperatorSlashingDto, OperatorsSnapshotData};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::collections::HashMap;
 
 pub async fn upsert_operators_snapshot_page(
     pool: &PgPool,
@@ -17,14 +19,15 @@ pub async fn upsert_operators_snapshot_page(
         sqlx::query(
             r#"
                 INSERT INTO operators_snapshot
-                        (operator_id, avs_count, strategy_count, slashing_count, last_slash_at, last_update_block_ts)
-                VALUES ($1,$2,$3,$4,$5,$6)
+                        (operator_id, avs_count, strategy_count, slashing_count, last_slash_at, last_update_block_ts, strategies_incomplete)
+                VALUES ($1,$2,$3,$4,$5,$6,$7)
                 ON CONFLICT (operator_id) DO UPDATE
                     SET avs_count = EXCLUDED.avs_count,
                         strategy_count = EXCLUDED.strategy_count,
                         slashing_count = EXCLUDED.slashing_count,
                         last_slash_at = EXCLUDED.last_slash_at,
-                        last_update_block_ts = EXCLUDED.last_update_block_ts
+                        last_update_block_ts = EXCLUDED.last_update_block_ts,
+                        strategies_incomplete = EXCLUDED.strategies_incomplete
                 WHERE operators_snapshot.last_update_block_ts < EXCLUDED.last_update_block_ts
                 "#,
         )
@@ -34,6 +37,7 @@ pub async fn upsert_operators_snapshot_page(
             .bind(op.slashing_count)
             .bind(last_slash_at)
             .bind(op.last_update_block_timestamp.parse::<i64>().unwrap_or(0))
+            .bind(op.strategies_incomplete)
             .execute(tx.as_mut())
             .await?;
 
@@ -64,7 +68,113 @@ pub async fn upsert_operators_snapshot_page(
                 .bind(&link.strategy.exchange_rate)
                 .execute(tx.as_mut()).await?;
         }
+
+        upsert_slashings(&mut tx, &op.id, &op.slashings).await?;
     }
 
     tx.commit().await
 }
+
+pub async fn upsert_operator_slashings(
+    pool: &PgPool,
+    operator_id: &str,
+    slashings: &[OperatorSlashingDto],
+) -> Result<(), sqlx::Error> {
+    let mut tx: Transaction<Postgres> = pool.begin().await?;
+    upsert_slashings(&mut tx, operator_id, slashings).await?;
+    tx.commit().await
+}
+
+async fn upsert_slashings(
+    tx: &mut Transaction<'_, Postgres>,
+    operator_id: &str,
+    slashings: &[OperatorSlashingDto],
+) -> Result<(), sqlx::Error> {
+    for slash in slashings {
+        let Ok(block_timestamp) = slash.block_timestamp.parse::<i64>() else {
+            continue;
+        };
+        let _t = DbTimer::new("upsert_slashing");
+        sqlx::query(
+            r#"
+                INSERT INTO operator_slashing
+                    (operator_id, slashing_id, block_number, block_timestamp, transaction_hash)
+                VALUES ($1,$2,$3,$4,$5)
+                ON CONFLICT (operator_id, slashing_id) DO UPDATE
+                    SET block_number = COALESCE(EXCLUDED.block_number, operator_slashing.block_number),
+                        transaction_hash = COALESCE(EXCLUDED.transaction_hash, operator_slashing.transaction_hash)
+            "#,
+        )
+        .bind(operator_id)
+        .bind(slash.id.as_deref().unwrap_or(&slash.block_timestamp))
+        .bind(slash.block_number.as_deref().and_then(|n| n.parse::<i64>().ok()))
+        .bind(block_timestamp)
+        .bind(&slash.transaction_hash)
+        .execute(tx.as_mut())
+        .await?;
+    }
+    Ok(())
+}
+
+/// Stored slashings per operator, for the operators in `operator_ids`.
+pub async fn select_slashing_counts(
+    pool: &PgPool,
+    operator_ids: &[String],
+) -> Result<HashMap<String, i64>, sqlx::Error> {
+    let _t = DbTimer::new("select_slashing_counts");
+    let rows = sqlx::query(
+        r#"
+            SELECT operator_id, COUNT(*) AS stored
+            FROM operator_slashing
+            WHERE operator_id = ANY($1)
+            GROUP BY operator_id
+        "#,
+    )
+    .bind(operator_ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| (r.get("operator_id"), r.get("stored")))
+        .collect())
+}
+
+pub async fn select_operator_slashings(
+    pool: &PgPool,
+    operator_id: &str,
+) -> Result<Vec<SlashingEvent>, sqlx::Error> {
+    let _t = DbTimer::new("select_operator_slashing");
+    let rows = sqlx::query(
+        r#"
+            SELECT slashing_id, block_number, block_timestamp, transaction_hash
+            FROM operator_slashing
+            WHERE operator_id = $1
+            ORDER BY block_timestamp DESC, slashing_id ASC
+        "#,
+    )
+    .bind(operator_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| SlashingEvent {
+            slashing_id: r.get("slashing_id"),
+            block_number: r.get("block_number"),
+            block_timestamp: r.get("block_timestamp"),
+            transaction_hash: r.get("transaction_hash"),
+        })
+        .collect())
+}
+
+/// Drops operators the last full ingest run did not see; their positions and
+/// slashings go with them through the cascading foreign keys.
+pub async fn delete_operators_not_in(pool: &PgPool, seen: &[String]) -> Result<u64, sqlx::Error> {
+    let _t = DbTimer::new("delete_stale_operators");
+    sqlx::query("DELETE FROM operators_snapshot WHERE NOT (operator_id = ANY($1))")
+        .bind(seen)
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+}
@@ -0,0 +1,26 @@
+use crate::metrics::DbTimer;
+use sqlx::{PgPool, Row};
+use std::collections::BTreeMap;
+
+pub async fn select_latest_prices(
+    pool: &PgPool,
+    product_ids: &[&str],
+) -> Result<BTreeMap<String, f64>, sqlx::Error> {
+    let _t = DbTimer::new("select_latest_ticks");
+    let rows = sqlx::query(
+        r#"
+            SELECT DISTINCT ON (product_id) product_id, price::float8 AS price
+            FROM ticks
+            WHERE product_id = ANY($1)
+            ORDER BY product_id, time DESC
+        "#,
+    )
+    .bind(product_ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| (r.get::<String, _>("product_id"), r.get::<f64, _>("price")))
+        .collect())
+}
@@ -0,0 +1,7 @@
+use crate::handlers::admin_handler::cache_purge_handler;
+use crate::state::AppState;
+use axum::{Router, routing::post};
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/admin/cache/purge", post(cache_purge_handler))
+}
@@ -0,0 +1,9 @@
+use crate::handlers::avs_handler::{avs_detail_handler, avs_list_handler};
+use crate::state::AppState;
+use axum::{Router, routing::get};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/avs", get(avs_list_handler))
+        .route("/avs/{id}", get(avs_detail_handler))
+}
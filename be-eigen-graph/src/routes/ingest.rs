@@ -0,0 +1,7 @@
+use crate::handlers::ingest_handler::ingest_status_handler;
+use crate::state::AppState;
+use axum::{Router, routing::get};
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/ingest/status", get(ingest_status_handler))
+}
@@ -0,0 +1,9 @@
+use crate::handlers::live_handler::{aggregates_sse_handler, aggregates_ws_handler};
+use crate::state::AppState;
+use axum::{Router, routing::get};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/operators/aggregates/ws", get(aggregates_ws_handler))
+        .route("/operators/aggregates/stream", get(aggregates_sse_handler))
+}
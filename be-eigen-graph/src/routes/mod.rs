@@ -1,4 +1,11 @@
+mod admin;
+mod avs;
+mod ingest;
+mod live;
+mod network;
 mod operators;
 pub mod operators_cached;
 mod ping;
+mod strategies;
+mod tokens;
 pub mod v1;
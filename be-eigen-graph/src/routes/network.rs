@@ -0,0 +1,7 @@
+use crate::handlers::network_handler::network_summary_handler;
+use crate::state::AppState;
+use axum::{Router, routing::get};
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/network/summary", get(network_summary_handler))
+}
@@ -1,4 +1,7 @@
-use crate::handlers::operators_handler::{operators_aggregates_handler, snapshot_handler};
+use crate::handlers::operators_handler::{
+    operator_detail_handler, operator_history_handler, operators_aggregates_handler,
+    operators_diff_handler, operators_risk_handler, snapshot_handler,
+};
 use crate::state::AppState;
 use axum::{Router, routing::get};
 
@@ -6,4 +9,8 @@ pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/operators/snapshot", get(snapshot_handler))
         .route("/operators/aggregates", get(operators_aggregates_handler))
+        .route("/operators/risk", get(operators_risk_handler))
+        .route("/operators/diff", get(operators_diff_handler))
+        .route("/operators/{id}", get(operator_detail_handler))
+        .route("/operators/{id}/history", get(operator_history_handler))
 }
@@ -0,0 +1,9 @@
+use crate::handlers::strategies_handler::{strategies_handler, strategy_detail_handler};
+use crate::state::AppState;
+use axum::{Router, routing::get};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/strategies", get(strategies_handler))
+        .route("/strategies/{id}", get(strategy_detail_handler))
+}
@@ -0,0 +1,7 @@
+use crate::handlers::tokens_handler::tokens_handler;
+use crate::state::AppState;
+use axum::{Router, routing::get};
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/tokens", get(tokens_handler))
+}
@@ -1,4 +1,6 @@
-use super::{operators, operators_cached, ping};
+use super::{
+    admin, avs, ingest, live, network, operators, operators_cached, ping, strategies, tokens,
+};
 use crate::state::AppState;
 use axum::Router;
 
@@ -7,4 +9,11 @@ pub fn routes() -> Router<AppState> {
         .merge(ping::routes())
         .merge(operators::routes())
         .merge(operators_cached::routes())
+        .merge(live::routes())
+        .merge(ingest::routes())
+        .merge(avs::routes())
+        .merge(strategies::routes())
+        .merge(tokens::routes())
+        .merge(network::routes())
+        .merge(admin::routes())
 }
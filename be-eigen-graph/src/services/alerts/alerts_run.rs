@@ -0,0 +1,72 @@
+use crate::models::ingest::{IngestStats, IngestStatus};
+use crate::models::operators_aggr::AggregatorParams;
+use crate::repositories::alerts::{apply_alert_transitions, select_alert_state};
+use crate::repositories::ingest_runs::{finish_ingest_run, last_ingest_run, start_ingest_run};
+use crate::services::alerts::outlier_rules::{active_alerts, alert_events, transitions};
+use crate::services::operators::operators_aggr::from_db_adapt::from_db_adapt_all;
+use crate::services::operators::operators_aggr::operators_aggregator::{
+    aggregate, apply_prices, detect_outliers,
+};
+use crate::state::AppState;
+
+pub const ALERTS_RUN_KIND: &str = "alerts";
+
+/// Evaluates the outlier rules over the stored snapshot and queues a webhook
+/// delivery for every operator that entered or left a list since the previous
+/// evaluation. The first evaluation ever only records state, so enabling
+/// alerts does not announce every existing outlier at once.
+pub async fn run_alerts(state: &AppState) -> Result<(IngestStatus, IngestStats), sqlx::Error> {
+    let seeded = last_ingest_run(&state.db, ALERTS_RUN_KIND, Some(IngestStatus::Succeeded))
+        .await?
+        .is_some();
+    let run_id = start_ingest_run(&state.db, ALERTS_RUN_KIND).await?;
+    let mut stats = IngestStats::default();
+
+    let status = match evaluate(state, seeded, &mut stats).await {
+        Ok(()) => IngestStatus::Succeeded,
+        Err(e) => {
+            stats.errors += 1;
+            stats.last_error = Some(e.to_string());
+            IngestStatus::Failed
+        }
+    };
+
+    finish_ingest_run(&state.db, run_id, status, &stats).await?;
+    Ok((status, stats))
+}
+
+async fn evaluate(
+    state: &AppState,
+    seeded: bool,
+    stats: &mut IngestStats,
+) -> Result<(), sqlx::Error> {
+    let now_ts = chrono::Utc::now().timestamp();
+    let page = from_db_adapt_all(&state.db).await?;
+    let mut aggr = aggregate(&page, &AggregatorParams::default(), now_ts);
+    // multi-token operators only have a strategy HHI once priced
+    apply_prices(&mut aggr, &page, &state.prices.latest().await);
+    let outliers = detect_outliers(
+        &aggr,
+        state.alert_hhi_threshold,
+        state.alert_recent_window_seconds,
+        now_ts,
+    );
+
+    let previous = select_alert_state(&state.db).await?;
+    let (entered, left) = transitions(&previous, &active_alerts(&outliers));
+    stats.pages = 1;
+    stats.rows = (entered.len() + left.len()) as i32;
+    let deliveries = if seeded {
+        state
+            .webhooks
+            .deliveries(&alert_events(&entered, &left, &aggr, now_ts))
+    } else {
+        Vec::new()
+    };
+    apply_alert_transitions(&state.db, &entered, &left, now_ts, &deliveries).await?;
+
+    if !deliveries.is_empty() {
+        state.webhooks.wake();
+    }
+    Ok(())
+}
@@ -0,0 +1,3 @@
+pub mod alerts_run;
+pub mod outlier_rules;
+pub mod webhook_sender;
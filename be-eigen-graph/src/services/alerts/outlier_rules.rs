@@ -0,0 +1,127 @@
+use crate::models::alerts::{AlertDetails, AlertEvent, AlertRule, Transition};
+use crate::models::operators_aggr::{OperatorAggregate, Outliers};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+
+pub type AlertKey = (AlertRule, String);
+
+/// Every (rule, operator) pair currently flagged by `detect_outliers`.
+pub fn active_alerts(outliers: &Outliers) -> BTreeSet<AlertKey> {
+    let lists = [
+        (AlertRule::HighConcentration, &outliers.high_concentration),
+        (AlertRule::ZeroShare, &outliers.zero_share),
+        (AlertRule::RecentSlash, &outliers.recent_slashes),
+    ];
+    lists
+        .into_iter()
+        .flat_map(|(rule, ids)| ids.iter().map(move |id| (rule, id.clone())))
+        .collect()
+}
+
+/// `(entered, left)` between the remembered and the current state.
+pub fn transitions(
+    previous: &BTreeSet<AlertKey>,
+    current: &BTreeSet<AlertKey>,
+) -> (Vec<AlertKey>, Vec<AlertKey>) {
+    let entered = current.difference(previous).cloned().collect();
+    let left = previous.difference(current).cloned().collect();
+    (entered, left)
+}
+
+pub fn alert_events(
+    entered: &[AlertKey],
+    left: &[AlertKey],
+    aggr: &[OperatorAggregate],
+    now_ts: i64,
+) -> Vec<AlertEvent> {
+    let by_id: HashMap<&str, &OperatorAggregate> =
+        aggr.iter().map(|a| (a.operator_id.as_str(), a)).collect();
+
+    let entered = entered.iter().map(|k| (k, Transition::Entered));
+    let left = left.iter().map(|k| (k, Transition::Left));
+    entered
+        .chain(left)
+        .map(|((rule, operator_id), transition)| AlertEvent {
+            id: event_id(*rule, operator_id, transition, now_ts),
+            event_type: transition.event_type(),
+            rule: *rule,
+            operator_id: operator_id.clone(),
+            occurred_at: now_ts,
+            details: by_id.get(operator_id.as_str()).map(|a| AlertDetails {
+                hhi_strategy: a.hhi_strategy,
+                zero_share: a.zero_share_flag,
+                last_slash_at: a.last_slash_at,
+                tvl_total_atomic: a.tvl_total_atomic.clone(),
+                tvl_total_decimal: a.tvl_total_decimal.clone(),
+            }),
+        })
+        .collect()
+}
+
+/// Stable per transition, so receivers can de-duplicate retried deliveries.
+fn event_id(rule: AlertRule, operator_id: &str, transition: Transition, now_ts: i64) -> String {
+    let digest = Sha256::digest(format!(
+        "{}|{}|{}|{}",
+        rule.as_str(),
+        operator_id,
+        transition.event_type(),
+        now_ts
+    ));
+    hex::encode(&digest[..16])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(rule: AlertRule, id: &str) -> AlertKey {
+        (rule, id.to_string())
+    }
+
+    #[test]
+    fn active_alerts_cover_every_rule() {
+        let outliers = Outliers {
+            high_concentration: vec!["0xa".to_string()],
+            zero_share: vec!["0xa".to_string(), "0xb".to_string()],
+            recent_slashes: vec![],
+        };
+        let active = active_alerts(&outliers);
+        assert_eq!(active.len(), 3);
+        assert!(active.contains(&key(AlertRule::HighConcentration, "0xa")));
+        assert!(active.contains(&key(AlertRule::ZeroShare, "0xb")));
+    }
+
+    #[test]
+    fn transitions_report_only_changes() {
+        let previous = BTreeSet::from([
+            key(AlertRule::ZeroShare, "0xa"),
+            key(AlertRule::RecentSlash, "0xb"),
+        ]);
+        let current = BTreeSet::from([
+            key(AlertRule::ZeroShare, "0xa"),
+            key(AlertRule::HighConcentration, "0xb"),
+        ]);
+        let (entered, left) = transitions(&previous, &current);
+        assert_eq!(entered, vec![key(AlertRule::HighConcentration, "0xb")]);
+        assert_eq!(left, vec![key(AlertRule::RecentSlash, "0xb")]);
+
+        let (entered, left) = transitions(&current, &current);
+        assert!(entered.is_empty() && left.is_empty());
+    }
+
+    #[test]
+    fn events_have_stable_distinct_ids() {
+        let k = [key(AlertRule::ZeroShare, "0xa")];
+        let events = alert_events(&k, &k, &[], 100);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, "outlier.entered");
+        assert_eq!(events[1].event_type, "outlier.left");
+        assert_ne!(events[0].id, events[1].id);
+        assert!(events[0].details.is_none());
+
+        let again = alert_events(&k, &[], &[], 100);
+        assert_eq!(again[0].id, events[0].id);
+        let later = alert_events(&k, &[], &[], 101);
+        assert_ne!(later[0].id, events[0].id);
+    }
+}
@@ -0,0 +1,347 @@
+use crate::metrics::{error_inc, webhook_inc};
+use crate::models::alerts::{
+    AlertEvent, DeliveryStatus, NewWebhookDelivery, PendingWebhookDelivery,
+};
+use crate::repositories::alerts::{record_webhook_attempt, select_due_webhook_deliveries};
+use futures::future::join_all;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use reqwest::{Client, StatusCode, Url};
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::{MissedTickBehavior, interval};
+
+pub const SIGNATURE_HEADER: &str = "x-eigen-graph-signature";
+pub const EVENT_HEADER: &str = "x-eigen-graph-event";
+pub const DELIVERY_HEADER: &str = "x-eigen-graph-delivery";
+
+#[derive(Clone, Debug)]
+pub struct WebhookOptions {
+    pub urls: Vec<Url>,
+    pub secret: Option<String>,
+    pub timeout: Duration,
+    pub max_attempts: u32,
+    pub backoff_base: Duration,
+    pub backoff_max: Duration,
+}
+
+/// Delivers queued alert events to every configured URL. Each body is signed
+/// with HMAC-SHA256 over `"{t}.{body}"` and sent as `t=<unix>,v1=<hex>` in
+/// `X-Eigen-Graph-Signature`, so receivers can reject replays by age.
+#[derive(Clone, Debug)]
+pub struct WebhookSender {
+    http: Client,
+    urls: Arc<Vec<Url>>,
+    secret: Option<Arc<str>>,
+    options: WebhookOptions,
+    wake: Arc<Notify>,
+}
+
+enum Attempt {
+    Delivered(StatusCode),
+    Retry(Option<StatusCode>, String),
+    Reject(Option<StatusCode>, String),
+}
+
+const DUE_BATCH: i64 = 100;
+
+impl WebhookSender {
+    pub fn new(options: WebhookOptions) -> Self {
+        let http = Client::builder()
+            .user_agent("eigen-graph-webhooks/0.1")
+            .timeout(options.timeout)
+            .build()
+            .expect("reqwest client");
+        Self {
+            http,
+            urls: Arc::new(options.urls.clone()),
+            secret: options.secret.as_deref().map(Arc::from),
+            options,
+            wake: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Unsigned delivery is never attempted: without a secret nothing is sent.
+    pub fn is_enabled(&self) -> bool {
+        !self.urls.is_empty() && self.secret.is_some()
+    }
+
+    /// One queued delivery per event and URL, in event order.
+    pub fn deliveries(&self, events: &[AlertEvent]) -> Vec<NewWebhookDelivery> {
+        if !self.is_enabled() {
+            return Vec::new();
+        }
+        let mut out = Vec::with_capacity(events.len() * self.urls.len());
+        for event in events {
+            let Ok(payload) = serde_json::to_value(event) else {
+                error_inc("webhook_encode");
+                continue;
+            };
+            out.extend(self.urls.iter().map(|url| NewWebhookDelivery {
+                event_id: event.id.clone(),
+                url: url.to_string(),
+                event_type: event.event_type,
+                rule: event.rule,
+                operator_id: event.operator_id.clone(),
+                payload: payload.clone(),
+            }));
+        }
+        out
+    }
+
+    /// Asks the worker to look for due deliveries now.
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+
+    /// Polls `webhook_deliveries` for due rows on a single task, so deliveries
+    /// to a URL never overlap and survive restarts.
+    pub fn spawn_worker(&self, pool: PgPool) {
+        if !self.is_enabled() {
+            return;
+        }
+        let sender = self.clone();
+        let every = self.options.backoff_base.max(Duration::from_secs(1));
+        tokio::spawn(async move {
+            let mut ticker = interval(every);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = sender.wake.notified() => {}
+                }
+                if sender.deliver_due(&pool).await.is_err() {
+                    error_inc("webhook_log");
+                }
+            }
+        });
+    }
+
+    /// Drains every URL's queue in order. Each pass attempts the head of
+    /// every URL whose head is due, concurrently across URLs; a head that is
+    /// backing off holds back the deliveries queued after it.
+    async fn deliver_due(&self, pool: &PgPool) -> Result<(), sqlx::Error> {
+        loop {
+            let heads = select_due_webhook_deliveries(pool, DUE_BATCH).await?;
+            if heads.is_empty() {
+                return Ok(());
+            }
+            let outcomes = join_all(heads.iter().map(|d| self.deliver(pool, d))).await;
+            if outcomes.iter().all(|s| *s == DeliveryStatus::Pending) {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn deliver(&self, pool: &PgPool, d: &PendingWebhookDelivery) -> DeliveryStatus {
+        let attempts = d.attempts + 1;
+        let outcome = match (self.secret.as_deref(), Url::parse(&d.url)) {
+            (Some(secret), Ok(url)) => self.attempt(&url, d, secret).await,
+            (None, _) => Attempt::Retry(None, "no webhook secret".to_string()),
+            (_, Err(e)) => Attempt::Reject(None, e.to_string()),
+        };
+        let (status, response_status, last_error) = match outcome {
+            Attempt::Delivered(code) => (DeliveryStatus::Delivered, Some(code), None),
+            Attempt::Reject(code, err) => (DeliveryStatus::Failed, code, Some(err)),
+            Attempt::Retry(code, err) if attempts as u32 >= self.options.max_attempts => {
+                (DeliveryStatus::Failed, code, Some(err))
+            }
+            Attempt::Retry(code, err) => (DeliveryStatus::Pending, code, Some(err)),
+        };
+
+        if status != DeliveryStatus::Pending {
+            webhook_inc(status.as_str());
+        }
+        if record_webhook_attempt(
+            pool,
+            d.delivery_id,
+            status,
+            attempts,
+            response_status.map(|c| i32::from(c.as_u16())),
+            last_error.as_deref(),
+            self.backoff(attempts.max(1) as u32),
+        )
+        .await
+        .is_err()
+        {
+            // unrecorded, so the row is still pending and will be re-sent
+            error_inc("webhook_log");
+            return DeliveryStatus::Pending;
+        }
+        status
+    }
+
+    async fn attempt(&self, url: &Url, d: &PendingWebhookDelivery, secret: &str) -> Attempt {
+        let body = d.payload.to_string();
+        let ts = chrono::Utc::now().timestamp();
+        let resp = self
+            .http
+            .post(url.clone())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature_header(secret, ts, &body))
+            .header(EVENT_HEADER, &d.event_type)
+            .header(DELIVERY_HEADER, &d.event_id)
+            .body(body)
+            .send()
+            .await;
+
+        match resp {
+            Ok(r) if r.status().is_success() => Attempt::Delivered(r.status()),
+            Ok(r)
+                if r.status().is_server_error()
+                    || r.status() == StatusCode::TOO_MANY_REQUESTS
+                    || r.status() == StatusCode::REQUEST_TIMEOUT =>
+            {
+                Attempt::Retry(Some(r.status()), format!("http {}", r.status()))
+            }
+            Ok(r) => Attempt::Reject(Some(r.status()), format!("http {}", r.status())),
+            Err(e) => Attempt::Retry(None, e.without_url().to_string()),
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self
+            .options
+            .backoff_base
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            .min(self.options.backoff_max);
+        let jitter_ms = rand::rng().random_range(0..=exp.as_millis() as u64 / 2);
+        exp / 2 + Duration::from_millis(jitter_ms)
+    }
+}
+
+pub fn signature_header(secret: &str, ts: i64, body: &str) -> String {
+    format!("t={ts},v1={}", sign(secret, ts, body))
+}
+
+/// Checks a `t=<unix>,v1=<hex>` header against `body`; signatures older than
+/// `tolerance_secs` are rejected.
+pub fn verify_signature(
+    secret: &str,
+    header: &str,
+    body: &str,
+    now_ts: i64,
+    tolerance_secs: i64,
+) -> bool {
+    let mut ts = None;
+    let mut sig = None;
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", v)) => ts = v.parse::<i64>().ok(),
+            Some(("v1", v)) => sig = hex::decode(v).ok(),
+            _ => {}
+        }
+    }
+    let (Some(ts), Some(sig)) = (ts, sig) else {
+        return false;
+    };
+    if (now_ts - ts).abs() > tolerance_secs {
+        return false;
+    }
+    mac(secret, ts, body).verify_slice(&sig).is_ok()
+}
+
+fn sign(secret: &str, ts: i64, body: &str) -> String {
+    hex::encode(mac(secret, ts, body).finalize().into_bytes())
+}
+
+fn mac(secret: &str, ts: i64, body: &str) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts any key length");
+    mac.update(ts.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::alerts::{AlertRule, Transition};
+
+    const BODY: &str = r#"{"id":"e1"}"#;
+
+    #[test]
+    fn signature_verifies_within_tolerance() {
+        let header = signature_header("secret", 1_000, BODY);
+        assert!(header.starts_with("t=1000,v1="));
+        assert!(verify_signature("secret", &header, BODY, 1_000, 300));
+        assert!(verify_signature("secret", &header, BODY, 1_300, 300));
+        assert!(!verify_signature("secret", &header, BODY, 1_301, 300));
+    }
+
+    #[test]
+    fn signature_rejects_wrong_secret_body_or_header() {
+        let header = signature_header("secret", 1_000, BODY);
+        assert!(!verify_signature("other", &header, BODY, 1_000, 300));
+        assert!(!verify_signature(
+            "secret",
+            &header,
+            r#"{"id":"e2"}"#,
+            1_000,
+            300
+        ));
+        // the timestamp is signed, so it cannot be moved forward
+        let replayed = header.replace("t=1000", "t=2000");
+        assert!(!verify_signature("secret", &replayed, BODY, 2_000, 300));
+        assert!(!verify_signature("secret", "t=1000", BODY, 1_000, 300));
+        assert!(!verify_signature(
+            "secret",
+            "t=1000,v1=zz",
+            BODY,
+            1_000,
+            300
+        ));
+    }
+
+    fn sender(urls: &[&str], secret: Option<&str>) -> WebhookSender {
+        WebhookSender::new(WebhookOptions {
+            urls: urls.iter().map(|u| Url::parse(u).unwrap()).collect(),
+            secret: secret.map(str::to_string),
+            timeout: Duration::from_secs(1),
+            max_attempts: 3,
+            backoff_base: Duration::from_secs(1),
+            backoff_max: Duration::from_secs(10),
+        })
+    }
+
+    fn event(id: &str) -> AlertEvent {
+        AlertEvent {
+            id: id.to_string(),
+            event_type: Transition::Entered.event_type(),
+            rule: AlertRule::ZeroShare,
+            operator_id: "0xop".to_string(),
+            occurred_at: 0,
+            details: None,
+        }
+    }
+
+    #[test]
+    fn deliveries_fan_out_per_url_in_event_order() {
+        let s = sender(&["http://a.test/hook", "http://b.test/hook"], Some("k"));
+        let queued = s.deliveries(&[event("e1"), event("e2")]);
+        let pairs: Vec<(&str, &str)> = queued
+            .iter()
+            .map(|d| (d.event_id.as_str(), d.url.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("e1", "http://a.test/hook"),
+                ("e1", "http://b.test/hook"),
+                ("e2", "http://a.test/hook"),
+                ("e2", "http://b.test/hook"),
+            ]
+        );
+    }
+
+    #[test]
+    fn nothing_is_queued_without_a_secret() {
+        let s = sender(&["http://a.test/hook"], None);
+        assert!(!s.is_enabled());
+        assert!(s.deliveries(&[event("e1")]).is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+use crate::api::subgraph::client::SubgraphClient;
+use crate::api::subgraph::errors::InfraError;
+use crate::api::subgraph::queries::{AVS_OPERATORS, AVS_PAGE};
+use crate::metrics::error_inc;
+use crate::models::avs::{AvsDto, AvsOperatorsData, AvsOperatorsVars, AvsPageData, AvsPageVars};
+use crate::services::operators::operators_cursor::next_cursor_for;
+
+const REGISTRATIONS_PAGE_SIZE: i32 = 1000;
+const MAX_REGISTRATION_PAGES: i32 = 20;
+
+pub async fn avs_page_after(
+    client: &SubgraphClient,
+    vars: AvsPageVars,
+) -> Result<AvsPageData, InfraError> {
+    let mut data: AvsPageData = client.query(AVS_PAGE, &vars).await?;
+    for avs in data.avss.iter_mut() {
+        complete_registrations_for(client, avs).await;
+    }
+    data.next_cursor = next_cursor_for(
+        data.avss.len(),
+        data.avss.last().map(|a| a.id.as_str()),
+        vars.first,
+    );
+    Ok(data)
+}
+
+pub async fn avs_detail(
+    client: &SubgraphClient,
+    avs_id: &str,
+) -> Result<Option<AvsDto>, InfraError> {
+    let vars = AvsOperatorsVars {
+        id: avs_id.to_string(),
+        first: REGISTRATIONS_PAGE_SIZE,
+        cursor: String::new(),
+    };
+    let data: AvsOperatorsData = client.query(AVS_OPERATORS, &vars).await?;
+    let Some(mut avs) = data.avs else {
+        return Ok(None);
+    };
+    complete_registrations_for(client, &mut avs).await;
+    Ok(Some(avs))
+}
+
+async fn complete_registrations_for(client: &SubgraphClient, avs: &mut AvsDto) {
+    if (avs.registrations.len() as i32) < REGISTRATIONS_PAGE_SIZE {
+        return;
+    }
+    if load_remaining_registrations(client, avs).await.is_err() {
+        error_inc("avs_registrations");
+        avs.registrations_incomplete = true;
+    }
+}
+
+/// Registrations are ordered by operator id, so each page continues after
+/// the last operator seen and never repeats one.
+async fn load_remaining_registrations(
+    client: &SubgraphClient,
+    avs: &mut AvsDto,
+) -> Result<(), InfraError> {
+    for _ in 1..MAX_REGISTRATION_PAGES {
+        let Some(cursor) = avs.registrations.last().map(|r| r.operator.id.clone()) else {
+            return Ok(());
+        };
+        let vars = AvsOperatorsVars {
+            id: avs.id.clone(),
+            first: REGISTRATIONS_PAGE_SIZE,
+            cursor,
+        };
+        let data: AvsOperatorsData = client.query(AVS_OPERATORS, &vars).await?;
+        let regs = data.avs.map(|a| a.registrations).unwrap_or_default();
+        let fetched = regs.len() as i32;
+        avs.registrations.extend(regs);
+        if fetched < REGISTRATIONS_PAGE_SIZE {
+            return Ok(());
+        }
+    }
+    error_inc("avs_registrations_capped");
+    avs.registrations_incomplete = true;
+    Ok(())
+}
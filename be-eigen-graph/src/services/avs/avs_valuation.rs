@@ -0,0 +1,95 @@
+use crate::models::avs::{AVS_REGISTERED, UniformAvs};
+use crate::models::operators_aggr::{AggregatorParams, PageMeta, UniformOperator, UniformPage};
+use crate::payloads::avs::{AvsOperatorView, AvsView};
+use crate::payloads::operators::TvlView;
+use crate::services::operators::operators_aggr::operators_aggregator;
+use crate::services::operators::operators_aggr::operators_part::partition_by_token;
+use crate::services::valuation::position_value::TokenAmount;
+use std::collections::BTreeMap;
+
+pub fn registered_operator_ids(avs: &UniformAvs) -> Vec<String> {
+    avs.registrations
+        .iter()
+        .filter(|r| r.status == AVS_REGISTERED)
+        .map(|r| r.operator_id.clone())
+        .collect()
+}
+
+/// Values an AVS from the snapshot of its registered operators. Operators
+/// missing from `operators` are listed with `valued: false`.
+pub fn value_avs(
+    avs: &UniformAvs,
+    operators: &[UniformOperator],
+    now_ts: i64,
+) -> (AvsView, Vec<AvsOperatorView>) {
+    let registered = registered_operator_ids(avs);
+    let page = UniformPage {
+        operators: operators
+            .iter()
+            .filter(|o| registered.contains(&o.operator_id))
+            .cloned()
+            .collect(),
+        page_meta: PageMeta {
+            first: registered.len() as i32,
+            skip: 0,
+            next_cursor: None,
+            block: None,
+        },
+    };
+    let params = AggregatorParams::default();
+
+    let aggregates: BTreeMap<String, _> = operators_aggregator::aggregate(&page, &params, now_ts)
+        .into_iter()
+        .map(|a| (a.operator_id.clone(), a))
+        .collect();
+
+    let tvl_by_token = partition_by_token(&page)
+        .into_iter()
+        .map(|(symbol, token_page)| {
+            let aggr = operators_aggregator::aggregate(&token_page, &params, now_ts);
+            let first = token_page
+                .operators
+                .first()
+                .and_then(|o| o.positions.first());
+            let total = TokenAmount {
+                atomic: operators_aggregator::token_tvl_atomic(&aggr),
+                decimals: first.map(|p| p.token_decimals.max(0) as u32).unwrap_or(0),
+            };
+            TvlView {
+                token: first.map(|p| p.token_id.clone()).unwrap_or_default(),
+                symbol,
+                amount_atomic: total.atomic_string(),
+                amount_decimal: total.decimal_string(),
+            }
+        })
+        .collect();
+
+    let operator_views = avs
+        .registrations
+        .iter()
+        .map(|r| {
+            let aggr = aggregates.get(&r.operator_id);
+            AvsOperatorView {
+                operator_id: r.operator_id.clone(),
+                registered: r.status == AVS_REGISTERED,
+                last_update_block_ts: r.last_update_block_ts,
+                valued: aggr.is_some(),
+                tvl_total_atomic: aggr
+                    .map(|a| a.tvl_total_atomic.clone())
+                    .unwrap_or_else(|| "0".to_string()),
+                hhi: aggr.and_then(|a| a.hhi_strategy),
+            }
+        })
+        .collect();
+
+    let view = AvsView {
+        avs_id: avs.avs_id.clone(),
+        operator_count: avs.operator_count,
+        registered_operator_count: registered.len(),
+        last_update_block_ts: avs.last_update_block_ts,
+        registrations_incomplete: avs.registrations_incomplete,
+        tvl_by_token,
+    };
+
+    (view, operator_views)
+}
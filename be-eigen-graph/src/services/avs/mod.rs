@@ -0,0 +1,2 @@
+pub mod avs_fetcher;
+pub mod avs_valuation;
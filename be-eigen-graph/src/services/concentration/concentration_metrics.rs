@@ -0,0 +1,134 @@
+use crate::models::operators_aggr::ConcentrationMetrics;
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+
+pub const TOP_K: usize = 3;
+
+/// Share of the total an entity set must exceed to count as a majority for
+/// the Nakamoto coefficient.
+pub const NAKAMOTO_THRESHOLD: f64 = 0.5;
+
+pub fn concentration_of(weights: &[BigUint]) -> ConcentrationMetrics {
+    let weights: Vec<f64> = weights.iter().map(|w| w.to_f64().unwrap_or(0.0)).collect();
+    concentration_of_f64(&weights)
+}
+
+/// All measures are computed over the non-zero weights: HHI and top-k share
+/// in 0..1, Gini in 0..1 (0 = equal), entropy normalised by `ln(n)` so 1 means
+/// perfectly spread, and the Nakamoto coefficient as the fewest entities whose
+/// combined share exceeds `NAKAMOTO_THRESHOLD`.
+pub fn concentration_of_f64(weights: &[f64]) -> ConcentrationMetrics {
+    let mut w: Vec<f64> = weights
+        .iter()
+        .copied()
+        .filter(|x| x.is_finite() && *x > 0.0)
+        .collect();
+    let total: f64 = w.iter().sum();
+    if w.is_empty() || total <= 0.0 || !total.is_finite() {
+        return ConcentrationMetrics::default();
+    }
+    w.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+
+    let n = w.len();
+    let shares: Vec<f64> = w.iter().map(|x| x / total).collect();
+
+    let hhi = shares.iter().map(|s| s * s).sum::<f64>().clamp(0.0, 1.0);
+
+    let top_k_share = shares.iter().take(TOP_K).sum::<f64>().clamp(0.0, 1.0);
+
+    let entropy = if n > 1 {
+        let h: f64 = shares.iter().map(|s| -s * s.ln()).sum();
+        (h / (n as f64).ln()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    // shares are sorted descending, so rank them ascending for the Gini sum
+    let gini = if n > 1 {
+        let weighted: f64 = shares
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, s)| (i as f64 + 1.0) * s)
+            .sum();
+        (2.0 * weighted / n as f64 - (n as f64 + 1.0) / n as f64).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    let mut cumulative = 0.0;
+    let mut nakamoto = n;
+    for (i, s) in shares.iter().enumerate() {
+        cumulative += s;
+        if cumulative > NAKAMOTO_THRESHOLD {
+            nakamoto = i + 1;
+            break;
+        }
+    }
+
+    ConcentrationMetrics {
+        count: n,
+        hhi,
+        gini,
+        entropy,
+        top_k: TOP_K,
+        top_k_share,
+        nakamoto: nakamoto as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn equal_weights_are_fully_spread() {
+        let m = concentration_of_f64(&[1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(m.count, 4);
+        assert!(close(m.hhi, 0.25));
+        assert!(close(m.gini, 0.0));
+        assert!(close(m.entropy, 1.0));
+        assert!(close(m.top_k_share, 0.75));
+        // two of four hold exactly half, which is not a majority
+        assert_eq!(m.nakamoto, 3);
+    }
+
+    #[test]
+    fn single_holder_is_fully_concentrated() {
+        let m = concentration_of_f64(&[5.0]);
+        assert_eq!(m.count, 1);
+        assert!(close(m.hhi, 1.0));
+        assert!(close(m.gini, 0.0));
+        assert!(close(m.entropy, 0.0));
+        assert_eq!(m.nakamoto, 1);
+    }
+
+    #[test]
+    fn uneven_pair() {
+        let m = concentration_of_f64(&[1.0, 3.0]);
+        assert!(close(m.hhi, 0.625));
+        assert!(close(m.gini, 0.25));
+        assert!(close(m.entropy, 0.811_278_124_459_132_8));
+        assert_eq!(m.nakamoto, 1);
+    }
+
+    #[test]
+    fn zero_and_invalid_weights_are_ignored() {
+        let m = concentration_of_f64(&[0.0, f64::NAN, -1.0, 2.0, 2.0]);
+        assert_eq!(m.count, 2);
+        assert!(close(m.hhi, 0.5));
+        assert_eq!(concentration_of_f64(&[]).count, 0);
+        assert_eq!(concentration_of_f64(&[0.0]).count, 0);
+    }
+
+    #[test]
+    fn big_weights_match_float_weights() {
+        let big = concentration_of(&[BigUint::from(10u32).pow(30), BigUint::from(10u32).pow(30)]);
+        assert!(close(big.hhi, 0.5));
+        assert_eq!(big.nakamoto, 2);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod concentration_metrics;
+pub mod network_summary;
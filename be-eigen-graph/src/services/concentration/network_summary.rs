@@ -0,0 +1,200 @@
+use crate::models::operators_aggr::{
+    AggregatorParams, NetworkSummary, StrategyAggregate, TokenConcentration, UniformPage,
+};
+use crate::services::concentration::concentration_metrics::{
+    concentration_of, concentration_of_f64,
+};
+use crate::services::operators::operators_aggr::operators_aggregator;
+use crate::services::operators::operators_aggr::operators_part::partition_by_token_id;
+use crate::services::operators::operators_aggr::strategies_aggregator::aggregate_strategies;
+use crate::services::prices::price_book::PriceBook;
+use crate::services::valuation::position_value::{TokenAmount, format_units};
+use num_bigint::BigUint;
+use num_traits::Zero;
+use std::collections::BTreeMap;
+
+pub const WEIGHTING_USD: &str = "usd";
+pub const WEIGHTING_PER_TOKEN: &str = "perToken";
+
+/// Network-wide concentration. Amounts of different tokens cannot be added,
+/// so operators and strategies are only weighted across the network by USD
+/// value, and only when every position is priced; otherwise the measures are
+/// reported per token alone.
+pub fn network_summary(page: &UniformPage, prices: &PriceBook, now_ts: i64) -> NetworkSummary {
+    let params = AggregatorParams::default();
+    let mut aggr = operators_aggregator::aggregate(page, &params, now_ts);
+    operators_aggregator::apply_prices(&mut aggr, page, prices);
+    let strategies = aggregate_strategies(page, now_ts);
+
+    let operator_usd: Option<Vec<f64>> = aggr
+        .iter()
+        .map(operators_aggregator::fully_priced_tvl_usd)
+        .collect();
+    let strategy_usd: Option<Vec<f64>> = strategies
+        .iter()
+        .map(|s| strategy_tvl_usd(s, prices))
+        .collect();
+
+    let (weighting, tvl_usd, operators, strategy_metrics) = match (operator_usd, strategy_usd) {
+        (Some(ops), Some(strats)) if !prices.is_empty() => (
+            WEIGHTING_USD,
+            Some(ops.iter().sum()),
+            Some(concentration_of_f64(&ops)),
+            Some(concentration_of_f64(&strats)),
+        ),
+        _ => (WEIGHTING_PER_TOKEN, None, None, None),
+    };
+
+    NetworkSummary {
+        operator_count: aggr.len(),
+        strategy_count: strategies.len(),
+        weighting: weighting.to_string(),
+        tvl_usd,
+        operators,
+        strategies: strategy_metrics,
+        by_token: by_token(page, now_ts),
+    }
+}
+
+/// `None` when a strategy holding anything has no price.
+fn strategy_tvl_usd(s: &StrategyAggregate, prices: &PriceBook) -> Option<f64> {
+    let atomic = BigUint::parse_bytes(s.tvl_total_atomic.as_bytes(), 10).unwrap_or_default();
+    if atomic.is_zero() {
+        return Some(0.0);
+    }
+    let amount = TokenAmount {
+        atomic,
+        decimals: s.token_decimals.max(0) as u32,
+    };
+    prices.usd_value(&s.token_symbol, &amount)
+}
+
+fn by_token(page: &UniformPage, now_ts: i64) -> BTreeMap<String, TokenConcentration> {
+    let params = AggregatorParams::default();
+    partition_by_token_id(page)
+        .into_iter()
+        .filter_map(|(token_id, token_page)| {
+            let first = token_page.operators.first()?.positions.first()?;
+            let symbol = first.token_symbol.clone();
+            let decimals = first.token_decimals;
+            let aggr = operators_aggregator::aggregate(&token_page, &params, now_ts);
+            let operator_weights: Vec<BigUint> = aggr
+                .iter()
+                .map(|a| operators_aggregator::token_tvl_atomic(std::slice::from_ref(a)))
+                .collect();
+            let total = operator_weights
+                .iter()
+                .fold(BigUint::zero(), |acc, v| acc + v);
+            let strategy_weights: Vec<BigUint> = aggregate_strategies(&token_page, now_ts)
+                .iter()
+                .map(|s| {
+                    BigUint::parse_bytes(s.tvl_total_atomic.as_bytes(), 10).unwrap_or_default()
+                })
+                .collect();
+
+            Some((
+                token_id,
+                TokenConcentration {
+                    symbol,
+                    tvl_total_atomic: total.to_string(),
+                    tvl_total_decimal: format_units(&total, decimals.max(0) as u32),
+                    operators: concentration_of(&operator_weights),
+                    strategies: concentration_of(&strategy_weights),
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::operators_aggr::{PageMeta, UniformOperator, UniformPosition};
+
+    const E18: &str = "000000000000000000";
+
+    fn operator(id: &str, positions: &[(&str, &str, u32)]) -> UniformOperator {
+        UniformOperator {
+            operator_id: id.to_string(),
+            avs_count: 1,
+            strategy_count: positions.len() as i32,
+            slashing_count: 0,
+            last_slash_at: None,
+            last_update_block_ts: 0,
+            positions: positions
+                .iter()
+                .map(|(strategy, symbol, whole)| UniformPosition {
+                    strategy_id: strategy.to_string(),
+                    token_id: format!("token-{symbol}"),
+                    token_symbol: symbol.to_string(),
+                    token_decimals: 18,
+                    total_shares: format!("{whole}{E18}"),
+                    exchange_rate: String::new(),
+                })
+                .collect(),
+            positions_incomplete: false,
+        }
+    }
+
+    fn page() -> UniformPage {
+        UniformPage {
+            operators: vec![
+                operator("0xa", &[("s-eth", "WETH", 1)]),
+                operator("0xb", &[("s-eigen", "EIGEN", 3000)]),
+            ],
+            page_meta: PageMeta {
+                first: 0,
+                skip: 0,
+                next_cursor: None,
+                block: None,
+            },
+        }
+    }
+
+    #[test]
+    fn priced_network_is_weighted_by_usd() {
+        let prices = PriceBook::new(BTreeMap::from([
+            ("WETH".to_string(), 3000.0),
+            ("EIGEN".to_string(), 1.0),
+        ]));
+        let summary = network_summary(&page(), &prices, 0);
+        assert_eq!(summary.weighting, WEIGHTING_USD);
+        assert_eq!(summary.tvl_usd, Some(6000.0));
+        // equal USD value, although the token amounts differ 3000-fold
+        let operators = summary.operators.unwrap();
+        assert!((operators.hhi - 0.5).abs() < 1e-9);
+        assert_eq!(operators.nakamoto, 2);
+    }
+
+    #[test]
+    fn unpriced_network_reports_per_token_only() {
+        let prices = PriceBook::new(BTreeMap::from([("WETH".to_string(), 3000.0)]));
+        let summary = network_summary(&page(), &prices, 0);
+        assert_eq!(summary.weighting, WEIGHTING_PER_TOKEN);
+        assert!(summary.tvl_usd.is_none());
+        assert!(summary.operators.is_none() && summary.strategies.is_none());
+        let eigen = &summary.by_token["token-EIGEN"];
+        assert_eq!(eigen.symbol, "EIGEN");
+        assert_eq!(eigen.tvl_total_atomic, format!("3000{E18}"));
+        assert_eq!(summary.by_token["token-WETH"].operators.count, 1);
+    }
+
+    #[test]
+    fn tokens_sharing_a_symbol_are_reported_apart() {
+        let mut op = operator("0xa", &[("s-native", "USDC", 5), ("s-bridged", "USDC", 2)]);
+        op.positions[0].token_id = "0xnative".to_string();
+        op.positions[0].token_decimals = 6;
+        op.positions[0].total_shares = "5000000".to_string();
+        op.positions[1].token_id = "0xbridged".to_string();
+        let page = UniformPage {
+            operators: vec![op],
+            ..page()
+        };
+
+        let summary = network_summary(&page, &PriceBook::default(), 0);
+        assert_eq!(summary.by_token.len(), 2);
+        assert_eq!(summary.by_token["0xnative"].tvl_total_decimal, "5");
+        assert_eq!(summary.by_token["0xbridged"].tvl_total_decimal, "2");
+        assert_eq!(summary.by_token["0xbridged"].symbol, "USDC");
+    }
+}
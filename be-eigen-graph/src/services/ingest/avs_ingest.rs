@@ -0,0 +1,60 @@
+use crate::metrics::error_inc;
+use crate::models::avs::{AvsPageVars, UniformAvs};
+use crate::models::ingest::{IngestStats, IngestStatus};
+use crate::repositories::avs::upsert_avs_page;
+use crate::repositories::ingest_runs::{finish_ingest_run, start_ingest_run};
+use crate::services::avs::avs_fetcher::avs_page_after;
+use crate::state::AppState;
+
+pub const AVS_INGEST_KIND: &str = "avs";
+
+pub async fn run_avs_ingest(
+    state: &AppState,
+    page_size: i32,
+) -> Result<(IngestStatus, IngestStats), sqlx::Error> {
+    let run_id = start_ingest_run(&state.db, AVS_INGEST_KIND).await?;
+    let mut stats = IngestStats::default();
+    let mut cursor = String::new();
+
+    let status = loop {
+        let vars = AvsPageVars {
+            first: page_size,
+            cursor: cursor.clone(),
+        };
+        let page = match avs_page_after(&state.subgraph_client, vars).await {
+            Ok(page) => page,
+            Err(e) => {
+                stats.errors += 1;
+                stats.last_error = Some(e.to_string());
+                break IngestStatus::Failed;
+            }
+        };
+
+        let fetched = page.avss.len() as i32;
+        let last_id = page.avss.last().map(|a| a.id.clone());
+        let avss: Vec<UniformAvs> = page.avss.iter().map(UniformAvs::from).collect();
+        stats.pages += 1;
+        match upsert_avs_page(&state.db, &avss).await {
+            Ok(()) => stats.rows += fetched,
+            Err(e) => {
+                error_inc("ingest_persist_page");
+                stats.errors += 1;
+                stats.last_error = Some(e.to_string());
+            }
+        }
+
+        match last_id {
+            Some(id) if fetched >= page_size => cursor = id,
+            _ => {
+                break if stats.errors == 0 {
+                    IngestStatus::Succeeded
+                } else {
+                    IngestStatus::Failed
+                };
+            }
+        }
+    };
+
+    finish_ingest_run(&state.db, run_id, status, &stats).await?;
+    Ok((status, stats))
+}
@@ -0,0 +1,2 @@
+pub mod avs_ingest;
+pub mod operators_ingest;
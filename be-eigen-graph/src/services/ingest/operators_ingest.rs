@@ -0,0 +1,132 @@
+use crate::metrics::error_inc;
+use crate::models::ingest::{IngestStats, IngestStatus};
+use crate::models::operators_snapshot::{BlockHeight, OperatorsSnapshotCursorVars};
+use crate::repositories::ingest_runs::{finish_ingest_run, start_ingest_run};
+use crate::repositories::operators::delete_operators_not_in;
+use crate::services::alerts::alerts_run::run_alerts;
+use crate::services::ingest::avs_ingest::run_avs_ingest;
+use crate::services::live::live_publish::publish_live_aggregates;
+use crate::services::operators::operators_fetcher::operators_snapshot_after;
+use crate::services::operators::operators_history::append_operators_history;
+use crate::services::operators::operators_repo::persist_operators_snapshot_db;
+use crate::services::operators::operators_slashings::sync_operator_slashings;
+use crate::state::AppState;
+use std::time::Duration;
+use tokio::time::{MissedTickBehavior, interval};
+
+pub const OPERATORS_INGEST_KIND: &str = "operators";
+
+pub fn spawn_operators_ingest(state: AppState, every: Duration, page_size: i32) {
+    tokio::spawn(async move {
+        let mut ticker = interval(every);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            // alerts and live aggregates only read a mirror that a run fully
+            // refreshed; a failed run is recorded in ingest_runs and skipped
+            match run_operators_ingest(&state, page_size).await {
+                Ok((IngestStatus::Succeeded, _)) => {
+                    if !matches!(run_alerts(&state).await, Ok((IngestStatus::Succeeded, _))) {
+                        error_inc("alerts_run");
+                    }
+                    if publish_live_aggregates(&state).await.is_err() {
+                        error_inc("live_publish");
+                    }
+                }
+                Ok(_) | Err(_) => error_inc("ingest_run"),
+            }
+            if !matches!(
+                run_avs_ingest(&state, page_size).await,
+                Ok((IngestStatus::Succeeded, _))
+            ) {
+                error_inc("ingest_run");
+            }
+        }
+    });
+}
+
+pub async fn run_operators_ingest(
+    state: &AppState,
+    page_size: i32,
+) -> Result<(IngestStatus, IngestStats), sqlx::Error> {
+    let run_id = start_ingest_run(&state.db, OPERATORS_INGEST_KIND).await?;
+    let mut stats = IngestStats::default();
+    let mut cursor = String::new();
+    // every page of one run shares a sample time so the run reads as one point
+    let captured_at = chrono::Utc::now().timestamp();
+    // and reads the block the first page was served at
+    let mut block: Option<BlockHeight> = None;
+    let mut seen: Vec<String> = Vec::new();
+
+    let status = loop {
+        let vars = OperatorsSnapshotCursorVars {
+            first: page_size,
+            cursor: cursor.clone(),
+            has_slashing: 0,
+            block,
+        };
+        let page = match operators_snapshot_after(&state.subgraph_client, vars).await {
+            Ok(page) => page,
+            Err(e) => {
+                stats.errors += 1;
+                stats.last_error = Some(e.to_string());
+                break IngestStatus::Failed;
+            }
+        };
+
+        if block.is_none() {
+            block = page.block_number().map(|number| BlockHeight { number });
+        }
+
+        let fetched = page.operators.len() as i32;
+        let last_id = page.operators.last().map(|o| o.id.clone());
+        seen.extend(page.operators.iter().map(|o| o.id.clone()));
+        stats.pages += 1;
+        match persist_operators_snapshot_db(&state.db, &page).await {
+            Ok(()) => stats.rows += fetched,
+            Err(e) => {
+                error_inc("ingest_persist_page");
+                stats.errors += 1;
+                stats.last_error = Some(e.to_string());
+            }
+        }
+        if let Err(e) = sync_operator_slashings(&state.db, &state.subgraph_client, &page).await {
+            error_inc("ingest_sync_slashings");
+            stats.errors += 1;
+            stats.last_error = Some(e.to_string());
+        }
+        if let Err(e) = append_operators_history(&state.db, &page, run_id, captured_at).await {
+            error_inc("ingest_append_history");
+            stats.errors += 1;
+            stats.last_error = Some(e.to_string());
+        }
+
+        match last_id {
+            Some(id) if fetched >= page_size => cursor = id,
+            _ => {
+                break if stats.errors == 0 {
+                    IngestStatus::Succeeded
+                } else {
+                    IngestStatus::Failed
+                };
+            }
+        }
+    };
+
+    // only a complete run knows which operators are gone upstream
+    if status == IngestStatus::Succeeded
+        && let Err(e) = delete_operators_not_in(&state.db, &seen).await
+    {
+        error_inc("ingest_prune_operators");
+        stats.errors += 1;
+        stats.last_error = Some(e.to_string());
+    }
+    let status = if stats.errors == 0 {
+        status
+    } else {
+        IngestStatus::Failed
+    };
+
+    finish_ingest_run(&state.db, run_id, status, &stats).await?;
+    Ok((status, stats))
+}
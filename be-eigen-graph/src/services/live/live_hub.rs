@@ -0,0 +1,91 @@
+use crate::models::live::LiveFrame;
+use crate::payloads::operators::{AggregatesResponse, TokenSlice};
+use crate::services::live::live_view::{LiveFilter, LiveSubscription};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::{RwLock, broadcast};
+
+const LIVE_CHANNEL_CAPACITY: usize = 16;
+
+/// Fans freshly ingested aggregates out to live subscribers. The latest frame
+/// is kept so a new subscriber starts from a snapshot instead of waiting for
+/// the next ingest.
+#[derive(Clone)]
+pub struct LiveHub {
+    tx: broadcast::Sender<Arc<LiveFrame>>,
+    latest: Arc<RwLock<Option<Arc<LiveFrame>>>>,
+}
+
+impl Default for LiveHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LiveHub {
+    pub fn new() -> Self {
+        let (tx, _rx) = broadcast::channel(LIVE_CHANNEL_CAPACITY);
+        Self {
+            tx,
+            latest: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    pub async fn publish(
+        &self,
+        aggregates: AggregatesResponse,
+        by_token_id: BTreeMap<String, TokenSlice>,
+        block: Option<i64>,
+        now_ts: i64,
+    ) {
+        self.store(aggregates, by_token_id, block, now_ts, false)
+            .await;
+    }
+
+    /// Publishes only if nothing has been published yet, so a slow startup
+    /// load never replaces the result of an ingest that finished first.
+    pub async fn publish_initial(
+        &self,
+        aggregates: AggregatesResponse,
+        by_token_id: BTreeMap<String, TokenSlice>,
+        block: Option<i64>,
+        now_ts: i64,
+    ) {
+        self.store(aggregates, by_token_id, block, now_ts, true)
+            .await;
+    }
+
+    pub async fn subscribe(&self, filter: LiveFilter) -> LiveSubscription {
+        // subscribe before reading `latest` so no frame published in between
+        // is missed; the subscription skips the duplicate
+        let rx = self.tx.subscribe();
+        let latest = self.latest.read().await.clone();
+        LiveSubscription::new(rx, latest, filter)
+    }
+
+    async fn store(
+        &self,
+        aggregates: AggregatesResponse,
+        by_token_id: BTreeMap<String, TokenSlice>,
+        block: Option<i64>,
+        now_ts: i64,
+        only_if_empty: bool,
+    ) {
+        // frames are numbered and sent under the lock so subscribers see
+        // them in `seq` order
+        let mut latest = self.latest.write().await;
+        if only_if_empty && latest.is_some() {
+            return;
+        }
+        let frame = Arc::new(LiveFrame {
+            seq: latest.as_ref().map_or(0, |f| f.seq) + 1,
+            published_at: now_ts,
+            block,
+            aggregates,
+            by_token_id,
+        });
+        *latest = Some(frame.clone());
+        // an error only means nobody is subscribed right now
+        let _ = self.tx.send(frame);
+    }
+}
@@ -0,0 +1,57 @@
+use crate::models::operators_aggr::AggregatorParams;
+use crate::payloads::operators::{AggregatesResponse, TokenSlice};
+use crate::repositories::operator_history::select_block_at;
+use crate::services::operators::operators_aggr::aggregates_response::{
+    build_aggregates_response, token_slices_by_id,
+};
+use crate::services::operators::operators_aggr::from_db_adapt::from_db_adapt_all;
+use crate::state::AppState;
+use std::collections::BTreeMap;
+
+/// Rebuilds the aggregates from the stored snapshot and pushes them to live
+/// subscribers; called after every successful operators ingest.
+pub async fn publish_live_aggregates(state: &AppState) -> Result<(), sqlx::Error> {
+    let now_ts = chrono::Utc::now().timestamp();
+    let (aggregates, by_token_id, block) = live_aggregates(state, now_ts).await?;
+    state
+        .live
+        .publish(aggregates, by_token_id, block, now_ts)
+        .await;
+    Ok(())
+}
+
+/// Seeds the hub from whatever is already stored, so subscribers get a
+/// snapshot before the first ingest of this process completes.
+pub async fn seed_live_aggregates(state: &AppState) -> Result<(), sqlx::Error> {
+    let now_ts = chrono::Utc::now().timestamp();
+    let (aggregates, by_token_id, block) = live_aggregates(state, now_ts).await?;
+    state
+        .live
+        .publish_initial(aggregates, by_token_id, block, now_ts)
+        .await;
+    Ok(())
+}
+
+async fn live_aggregates(
+    state: &AppState,
+    now_ts: i64,
+) -> Result<
+    (
+        AggregatesResponse,
+        BTreeMap<String, TokenSlice>,
+        Option<i64>,
+    ),
+    sqlx::Error,
+> {
+    let page = from_db_adapt_all(&state.db).await?;
+    let block = select_block_at(&state.db, now_ts).await?;
+    let prices = state.prices.latest().await;
+    let params = AggregatorParams {
+        hhi_threshold: state.alert_hhi_threshold,
+        recent_window_s: state.alert_recent_window_seconds,
+        ..AggregatorParams::default()
+    };
+    let aggregates = build_aggregates_response(&page, &params, &prices, "db", now_ts);
+    let by_token_id = token_slices_by_id(&page, &params, &prices, "db", now_ts);
+    Ok((aggregates, by_token_id, block))
+}
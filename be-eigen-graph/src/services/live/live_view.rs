@@ -0,0 +1,189 @@
+use crate::models::live::LiveFrame;
+use crate::models::operators_aggr::{BarItem, Outliers, TableRow};
+use crate::payloads::operators::{LiveMessage, LivePatch, LiveQuery, LiveSnapshot};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+#[derive(Clone, Debug, Default)]
+pub struct LiveFilter {
+    pub token: Option<String>,
+    pub operator_id: Option<String>,
+}
+
+impl LiveFilter {
+    pub fn from_query(q: &LiveQuery) -> Self {
+        Self {
+            token: q
+                .token
+                .as_deref()
+                .filter(|t| !t.is_empty())
+                .map(str::to_lowercase),
+            operator_id: q
+                .operator_id
+                .as_deref()
+                .filter(|id| !id.is_empty())
+                .map(str::to_lowercase),
+        }
+    }
+}
+
+/// The part of a frame one subscriber sees.
+#[derive(Debug, Default)]
+struct LiveView {
+    table: Vec<TableRow>,
+    bar: Vec<BarItem>,
+    outliers: Outliers,
+}
+
+impl LiveView {
+    fn of(frame: &LiveFrame, filter: &LiveFilter) -> Self {
+        let aggr = &frame.aggregates;
+        let (table, bar, outliers) = match filter.token.as_deref() {
+            None => (&aggr.table, &aggr.bar, &aggr.outliers),
+            Some(token) => {
+                let slice = frame
+                    .by_token_id
+                    .iter()
+                    .find(|(id, _)| id.to_lowercase() == token)
+                    .map(|(_, slice)| slice);
+                match slice {
+                    Some(t) => (&t.table, &t.bar, &t.outliers),
+                    None => return Self::default(),
+                }
+            }
+        };
+
+        let Some(id) = filter.operator_id.as_deref() else {
+            return Self {
+                table: table.clone(),
+                bar: bar.clone(),
+                outliers: outliers.clone(),
+            };
+        };
+        let only = |ids: &Vec<String>| ids.iter().filter(|o| *o == id).cloned().collect();
+        Self {
+            table: table
+                .iter()
+                .filter(|r| r.operator_id == id)
+                .cloned()
+                .collect(),
+            bar: bar
+                .iter()
+                .filter(|b| b.operator_id == id)
+                .cloned()
+                .collect(),
+            outliers: Outliers {
+                high_concentration: only(&outliers.high_concentration),
+                zero_share: only(&outliers.zero_share),
+                recent_slashes: only(&outliers.recent_slashes),
+            },
+        }
+    }
+
+    fn snapshot(&self, frame: &LiveFrame) -> LiveMessage {
+        LiveMessage::Snapshot(LiveSnapshot {
+            seq: frame.seq,
+            published_at: frame.published_at,
+            block: frame.block,
+            table: self.table.clone(),
+            bar: self.bar.clone(),
+            outliers: self.outliers.clone(),
+        })
+    }
+
+    /// `None` when nothing this subscriber sees has changed.
+    fn patch_from(&self, prev: &LiveView, frame: &LiveFrame) -> Option<LiveMessage> {
+        let prev_rows: HashMap<&str, &TableRow> = prev
+            .table
+            .iter()
+            .map(|r| (r.operator_id.as_str(), r))
+            .collect();
+        let ids: HashSet<&str> = self.table.iter().map(|r| r.operator_id.as_str()).collect();
+
+        let upserted: Vec<TableRow> = self
+            .table
+            .iter()
+            .filter(|r| prev_rows.get(r.operator_id.as_str()) != Some(r))
+            .cloned()
+            .collect();
+        let removed: Vec<String> = prev
+            .table
+            .iter()
+            .filter(|r| !ids.contains(r.operator_id.as_str()))
+            .map(|r| r.operator_id.clone())
+            .collect();
+        let bar = (self.bar != prev.bar).then(|| self.bar.clone());
+        let outliers = (self.outliers != prev.outliers).then(|| self.outliers.clone());
+
+        if upserted.is_empty() && removed.is_empty() && bar.is_none() && outliers.is_none() {
+            return None;
+        }
+        Some(LiveMessage::Patch(LivePatch {
+            seq: frame.seq,
+            published_at: frame.published_at,
+            block: frame.block,
+            upserted,
+            removed,
+            bar,
+            outliers,
+        }))
+    }
+}
+
+/// Turns published frames into the messages of one connection: a snapshot
+/// first, then a patch against the previous view whenever it changes. A
+/// subscriber that falls behind skips the frames it missed; the next patch
+/// still covers every change since its last message.
+pub struct LiveSubscription {
+    rx: broadcast::Receiver<Arc<LiveFrame>>,
+    pending: Option<Arc<LiveFrame>>,
+    filter: LiveFilter,
+    last: Option<LiveView>,
+    seq: u64,
+}
+
+impl LiveSubscription {
+    pub fn new(
+        rx: broadcast::Receiver<Arc<LiveFrame>>,
+        latest: Option<Arc<LiveFrame>>,
+        filter: LiveFilter,
+    ) -> Self {
+        Self {
+            rx,
+            pending: latest,
+            filter,
+            last: None,
+            seq: 0,
+        }
+    }
+
+    /// `None` once the hub is gone.
+    pub async fn next(&mut self) -> Option<LiveMessage> {
+        loop {
+            let frame = match self.pending.take() {
+                Some(frame) => frame,
+                None => match self.rx.recv().await {
+                    Ok(frame) => frame,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                },
+            };
+            if frame.seq <= self.seq {
+                continue;
+            }
+            self.seq = frame.seq;
+
+            let view = LiveView::of(&frame, &self.filter);
+            let msg = match &self.last {
+                None => Some(view.snapshot(&frame)),
+                Some(prev) => view.patch_from(prev, &frame),
+            };
+            self.last = Some(view);
+            if let Some(msg) = msg {
+                return Some(msg);
+            }
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod live_hub;
+pub mod live_publish;
+pub mod live_view;
@@ -1 +1,9 @@
+pub mod alerts;
+pub mod avs;
+pub mod concentration;
+pub mod ingest;
+pub mod live;
 pub mod operators;
+pub mod prices;
+pub mod tokens;
+pub mod valuation;
@@ -1,8 +1,14 @@
+pub mod operator_detail;
 pub mod operators_aggr;
 pub mod operators_aggregates_cached;
-pub mod operators_cache;
+pub mod operators_cursor;
+pub mod operators_diff;
 pub mod operators_fetcher;
 pub mod operators_filter;
+pub mod operators_history;
 pub mod operators_mapper;
 pub mod operators_repo;
+pub mod operators_slashings;
 pub mod operators_snapshot_cached;
+pub mod operators_source;
+pub mod operators_strategies;
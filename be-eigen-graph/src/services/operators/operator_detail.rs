@@ -0,0 +1,195 @@
+use crate::api::subgraph::client::SubgraphClient;
+use crate::api::subgraph::errors::InfraError;
+use crate::api::subgraph::queries::OPERATOR_DETAIL;
+use crate::models::operator_detail::{OperatorDetail, SlashingEvent};
+use crate::models::operators_aggr::{AggregatorParams, PageMeta, UniformPage};
+use crate::models::operators_snapshot::{
+    OperatorDetailData, OperatorDetailVars, OperatorDto, OperatorSlashingDto, OperatorsSnapshotData,
+};
+use crate::payloads::operators::{OperatorDetailView, OperatorOutlierFlags, PositionView, TvlView};
+use crate::repositories::operators::select_operator_slashings;
+use crate::services::operators::operators_aggr::from_db_adapt::from_db_operator;
+use crate::services::operators::operators_aggr::from_subgraph_adapt::from_subgraph_adapt;
+use crate::services::operators::operators_aggr::operators_aggregator;
+use crate::services::operators::operators_slashings::{SLASHINGS_PAGE_SIZE, load_slashings_after};
+use crate::services::operators::operators_strategies::complete_strategies_for;
+use crate::services::valuation::position_value::{TokenAmount, position_value};
+use sqlx::{Pool, Postgres};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+pub async fn operator_detail_live(
+    client: &SubgraphClient,
+    operator_id: &str,
+) -> Result<Option<OperatorDto>, InfraError> {
+    let vars = OperatorDetailVars {
+        id: operator_id.to_string(),
+        first: SLASHINGS_PAGE_SIZE,
+    };
+    let data: OperatorDetailData = client.query(OPERATOR_DETAIL, &vars).await?;
+    let Some(mut op) = data.operator else {
+        return Ok(None);
+    };
+
+    complete_strategies_for(client, &mut op).await;
+
+    if op.slashings.len() as i32 >= SLASHINGS_PAGE_SIZE {
+        let rest = load_slashings_after(client, &op.id, op.slashings.last()).await?;
+        op.slashings.extend(rest.slashings);
+        op.slashings_incomplete = rest.incomplete;
+    }
+
+    Ok(Some(op))
+}
+
+pub async fn operator_detail_db(
+    pool: &Pool<Postgres>,
+    operator_id: &str,
+) -> Result<Option<OperatorDetail>, sqlx::Error> {
+    let Some(operator) = from_db_operator(pool, operator_id).await? else {
+        return Ok(None);
+    };
+    let slashings = select_operator_slashings(pool, operator_id).await?;
+    // the ingest stores a capped list as far as it got
+    let slashings_incomplete = (slashings.len() as i64) < i64::from(operator.slashing_count);
+    Ok(Some(OperatorDetail {
+        operator,
+        slashings,
+        slashings_incomplete,
+    }))
+}
+
+pub fn operator_detail_from_dto(op: OperatorDto) -> Option<OperatorDetail> {
+    let slashings = op.slashings.iter().filter_map(slashing_from_dto).collect();
+    let slashings_incomplete = op.slashings_incomplete;
+    let page = OperatorsSnapshotData {
+        operators: vec![op],
+        next_cursor: None,
+        meta: None,
+    };
+    let operator = from_subgraph_adapt(&page, 1, 0)
+        .operators
+        .into_iter()
+        .next()?;
+    Some(OperatorDetail {
+        operator,
+        slashings,
+        slashings_incomplete,
+    })
+}
+
+pub fn build_operator_detail_view(
+    detail: OperatorDetail,
+    source: &str,
+    params: &AggregatorParams,
+    now_ts: i64,
+) -> Option<OperatorDetailView> {
+    let OperatorDetail {
+        operator,
+        mut slashings,
+        slashings_incomplete,
+    } = detail;
+    slashings.sort_by_key(|s| Reverse(s.block_timestamp));
+
+    let page = UniformPage {
+        operators: vec![operator.clone()],
+        page_meta: PageMeta {
+            first: 1,
+            skip: 0,
+            next_cursor: None,
+            block: None,
+        },
+    };
+    let aggr_params = AggregatorParams {
+        min_tvl_atomic: None,
+        ..params.clone()
+    };
+    let aggregates = operators_aggregator::aggregate(&page, &aggr_params, now_ts);
+    let aggregate = aggregates.first()?;
+    let outliers = operators_aggregator::detect_outliers(
+        &aggregates,
+        params.hhi_threshold,
+        params.recent_window_s,
+        now_ts,
+    );
+
+    let slices: BTreeMap<&str, Option<f64>> = aggregate
+        .strategy_breakdown
+        .iter()
+        .map(|s| (s.strategy_id.as_str(), s.share))
+        .collect();
+
+    let mut tvl_by_token: BTreeMap<String, (String, TokenAmount)> = BTreeMap::new();
+    let positions = operator
+        .positions
+        .iter()
+        .map(|p| {
+            let share = slices.get(p.strategy_id.as_str()).copied().flatten();
+            let amount = position_value(&p.total_shares, &p.exchange_rate, p.token_decimals);
+            let entry = tvl_by_token
+                .entry(p.token_id.clone())
+                .or_insert_with(|| (p.token_symbol.clone(), TokenAmount::zero(amount.decimals)));
+            entry.1.atomic += &amount.atomic;
+
+            PositionView {
+                strategy_id: p.strategy_id.clone(),
+                token_id: p.token_id.clone(),
+                token_symbol: p.token_symbol.clone(),
+                token_decimals: p.token_decimals,
+                total_shares: p.total_shares.clone(),
+                exchange_rate: p.exchange_rate.clone(),
+                tvl_atomic: amount.atomic_string(),
+                tvl_decimal: amount.decimal_string(),
+                share,
+            }
+        })
+        .collect();
+
+    let last_slash_at = slashings
+        .first()
+        .map(|s| s.block_timestamp)
+        .or(operator.last_slash_at);
+
+    Some(OperatorDetailView {
+        source: source.to_string(),
+        operator_id: operator.operator_id.clone(),
+        avs_count: operator.avs_count,
+        strategy_count: operator.strategy_count,
+        slashing_count: operator.slashing_count,
+        last_slash_at,
+        last_update_block_ts: operator.last_update_block_ts,
+        tvl_total_atomic: aggregate.tvl_total_atomic.clone(),
+        tvl_total_decimal: aggregate.tvl_total_decimal.clone(),
+        hhi: aggregate.hhi_strategy,
+        top_strategy_share: aggregate.top_strategy_share,
+        positions_incomplete: operator.positions_incomplete,
+        positions,
+        tvl_by_token: tvl_by_token
+            .into_iter()
+            .map(|(token, (symbol, amount))| TvlView {
+                token,
+                symbol,
+                amount_atomic: amount.atomic_string(),
+                amount_decimal: amount.decimal_string(),
+            })
+            .collect(),
+        slashings,
+        slashings_incomplete,
+        outliers: OperatorOutlierFlags {
+            high_concentration: !outliers.high_concentration.is_empty(),
+            zero_share: !outliers.zero_share.is_empty(),
+            recent_slash: last_slash_at
+                .is_some_and(|ts| ts >= now_ts.saturating_sub(params.recent_window_s)),
+        },
+    })
+}
+
+fn slashing_from_dto(s: &OperatorSlashingDto) -> Option<SlashingEvent> {
+    let block_timestamp = s.block_timestamp.parse::<i64>().ok()?;
+    Some(SlashingEvent {
+        slashing_id: s.id.clone().unwrap_or_else(|| s.block_timestamp.clone()),
+        block_number: s.block_number.as_deref().and_then(|n| n.parse().ok()),
+        block_timestamp,
+        transaction_hash: s.transaction_hash.clone(),
+    })
+}
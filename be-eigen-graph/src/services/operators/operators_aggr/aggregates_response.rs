@@ -0,0 +1,124 @@
+use crate::models::operators_aggr::{AggregatorParams, OperatorAggregate, UniformPage};
+use crate::payloads::operators::{AggregatesMeta, AggregatesResponse, TokenSlice};
+use crate::services::operators::operators_aggr::operators_aggregator;
+use crate::services::operators::operators_aggr::operators_part::{
+    partition_by_token, partition_by_token_id,
+};
+use crate::services::prices::price_book::PriceBook;
+use std::collections::BTreeMap;
+
+/// Runs the aggregation pipeline over one page, for the whole page and for
+/// each token slice, regardless of which source produced the page.
+pub fn build_aggregates_response(
+    page: &UniformPage,
+    params: &AggregatorParams,
+    prices: &PriceBook,
+    source: &str,
+    now_ts: i64,
+) -> AggregatesResponse {
+    let mut aggregates = operators_aggregator::aggregate(page, params, now_ts);
+    operators_aggregator::apply_prices(&mut aggregates, page, prices);
+
+    let by_token = token_slices(
+        page,
+        partition_by_token(page),
+        params,
+        prices,
+        source,
+        now_ts,
+    );
+
+    AggregatesResponse {
+        meta: meta_for(page, source, aggregates.len()),
+        table: operators_aggregator::to_table_rows(&aggregates),
+        bar: operators_aggregator::to_bar_series(&aggregates, params.top_n),
+        donut: donut_value(&aggregates, params),
+        graph: operators_aggregator::to_graph_edges(&aggregates),
+        outliers: operators_aggregator::detect_outliers(
+            &aggregates,
+            params.hhi_threshold,
+            params.recent_window_s,
+            now_ts,
+        ),
+        by_token,
+    }
+}
+
+/// The per-token slices keyed by token address rather than symbol, so
+/// distinct tokens that share a symbol stay apart.
+pub fn token_slices_by_id(
+    page: &UniformPage,
+    params: &AggregatorParams,
+    prices: &PriceBook,
+    source: &str,
+    now_ts: i64,
+) -> BTreeMap<String, TokenSlice> {
+    token_slices(
+        page,
+        partition_by_token_id(page),
+        params,
+        prices,
+        source,
+        now_ts,
+    )
+}
+
+fn token_slices(
+    page: &UniformPage,
+    partitions: BTreeMap<String, UniformPage>,
+    params: &AggregatorParams,
+    prices: &PriceBook,
+    source: &str,
+    now_ts: i64,
+) -> BTreeMap<String, TokenSlice> {
+    partitions
+        .into_iter()
+        .map(|(key, token_page)| {
+            let mut aggr_tok = operators_aggregator::aggregate(&token_page, params, now_ts);
+            operators_aggregator::apply_prices(&mut aggr_tok, &token_page, prices);
+
+            let slice = TokenSlice {
+                meta: meta_for(page, source, aggr_tok.len()),
+                table: operators_aggregator::to_table_rows(&aggr_tok),
+                bar: operators_aggregator::to_bar_series(&aggr_tok, params.top_n),
+                donut: donut_value(&aggr_tok, params),
+                graph: operators_aggregator::to_graph_edges(&aggr_tok),
+                outliers: operators_aggregator::detect_outliers(
+                    &aggr_tok,
+                    params.hhi_threshold,
+                    params.recent_window_s,
+                    now_ts,
+                ),
+            };
+            (key, slice)
+        })
+        .collect()
+}
+
+fn meta_for(page: &UniformPage, source: &str, count: usize) -> AggregatesMeta {
+    AggregatesMeta {
+        source: source.to_string(),
+        first: page.page_meta.first,
+        skip: page.page_meta.skip,
+        count,
+        next_cursor: page.page_meta.next_cursor.clone(),
+        block: page.page_meta.block,
+    }
+}
+
+fn donut_value(aggr: &[OperatorAggregate], params: &AggregatorParams) -> serde_json::Value {
+    let donuts = operators_aggregator::to_donuts(aggr, &params.focus_operator_id);
+    if params.focus_operator_id.is_some() {
+        donuts
+            .into_iter()
+            .next()
+            .and_then(|d| serde_json::to_value(d).ok())
+            .unwrap_or(serde_json::json!({}))
+    } else {
+        let map = donuts
+            .into_iter()
+            .map(|d| (d.operator_id.clone(), d))
+            .collect::<BTreeMap<_, _>>();
+        serde_json::to_value(map).unwrap_or(serde_json::json!({}))
+    }
+}
@@ -1,6 +1,10 @@
 use crate::metrics::DbTimer;
+use crate::models::ingest::IngestStatus;
 use crate::models::operators_aggr::{PageMeta, UniformOperator, UniformPage, UniformPosition};
+use crate::models::operators_snapshot::AsOf;
+use crate::services::operators::operators_cursor::next_cursor_for;
 use sqlx::Row;
+use sqlx::postgres::PgRow;
 use sqlx::{Pool, Postgres};
 use std::collections::BTreeMap;
 
@@ -13,7 +17,7 @@ pub async fn from_db_adapt(
     let ops_rows = sqlx::query(
         r#"
     SELECT operator_id, avs_count, strategy_count, slashing_count,
-           last_slash_at, last_update_block_ts
+           last_slash_at, last_update_block_ts, strategies_incomplete
     FROM operators_snapshot
     ORDER BY last_update_block_ts DESC, operator_id ASC
     LIMIT $1 OFFSET $2
@@ -24,11 +28,319 @@ pub async fn from_db_adapt(
     .fetch_all(pool)
     .await?;
 
+    let operators = load_operators(pool, ops_rows).await?;
+
+    Ok(UniformPage {
+        operators,
+        page_meta: PageMeta {
+            first,
+            skip,
+            next_cursor: None,
+            block: None,
+        },
+    })
+}
+
+pub async fn from_db_adapt_after(
+    pool: &Pool<Postgres>,
+    first: i32,
+    cursor: &str,
+) -> Result<UniformPage, sqlx::Error> {
+    let _t = DbTimer::new("select_operators_snapshot_after");
+    let ops_rows = sqlx::query(
+        r#"
+    SELECT operator_id, avs_count, strategy_count, slashing_count,
+           last_slash_at, last_update_block_ts, strategies_incomplete
+    FROM operators_snapshot
+    WHERE operator_id > $2
+    ORDER BY operator_id ASC
+    LIMIT $1
+    "#,
+    )
+    .bind(first as i64)
+    .bind(cursor)
+    .fetch_all(pool)
+    .await?;
+
+    let operators = load_operators(pool, ops_rows).await?;
+    let next_cursor = next_cursor_for(
+        operators.len(),
+        operators.last().map(|o| o.operator_id.as_str()),
+        first,
+    );
+
+    Ok(UniformPage {
+        operators,
+        page_meta: PageMeta {
+            first,
+            skip: 0,
+            next_cursor,
+            block: None,
+        },
+    })
+}
+
+pub async fn from_db_adapt_all(pool: &Pool<Postgres>) -> Result<UniformPage, sqlx::Error> {
+    let _t = DbTimer::new("select_operators_snapshot_all");
+    let ops_rows = sqlx::query(
+        r#"
+    SELECT operator_id, avs_count, strategy_count, slashing_count,
+           last_slash_at, last_update_block_ts, strategies_incomplete
+    FROM operators_snapshot
+    ORDER BY operator_id ASC
+    "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let operators = load_operators(pool, ops_rows).await?;
+    let first = operators.len() as i32;
+
+    Ok(UniformPage {
+        operators,
+        page_meta: PageMeta {
+            first,
+            skip: 0,
+            next_cursor: None,
+            block: None,
+        },
+    })
+}
+
+pub async fn from_db_strategy_operators(
+    pool: &Pool<Postgres>,
+    strategy_id: &str,
+) -> Result<UniformPage, sqlx::Error> {
+    let _t = DbTimer::new("select_operators_by_strategy");
+    let ops_rows = sqlx::query(
+        r#"
+    SELECT s.operator_id, s.avs_count, s.strategy_count, s.slashing_count,
+           s.last_slash_at, s.last_update_block_ts, s.strategies_incomplete
+    FROM operators_snapshot s
+    WHERE EXISTS (
+        SELECT 1 FROM operator_strategy p
+        WHERE p.operator_id = s.operator_id AND p.strategy_id = $1
+    )
+    ORDER BY s.operator_id ASC
+    "#,
+    )
+    .bind(strategy_id)
+    .fetch_all(pool)
+    .await?;
+
+    let operators = load_operators(pool, ops_rows).await?;
+    let first = operators.len() as i32;
+
+    Ok(UniformPage {
+        operators,
+        page_meta: PageMeta {
+            first,
+            skip: 0,
+            next_cursor: None,
+            block: None,
+        },
+    })
+}
+
+pub async fn from_db_operator(
+    pool: &Pool<Postgres>,
+    operator_id: &str,
+) -> Result<Option<UniformOperator>, sqlx::Error> {
+    let _t = DbTimer::new("select_operator_snapshot");
+    let ops_rows = sqlx::query(
+        r#"
+    SELECT operator_id, avs_count, strategy_count, slashing_count,
+           last_slash_at, last_update_block_ts, strategies_incomplete
+    FROM operators_snapshot
+    WHERE operator_id = $1
+    "#,
+    )
+    .bind(operator_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(load_operators(pool, ops_rows).await?.into_iter().next())
+}
+
+pub async fn from_db_operators(
+    pool: &Pool<Postgres>,
+    operator_ids: &[String],
+) -> Result<Vec<UniformOperator>, sqlx::Error> {
+    if operator_ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let _t = DbTimer::new("select_operators_snapshot_by_ids");
+    let id_refs: Vec<&str> = operator_ids.iter().map(AsRef::as_ref).collect();
+    let ops_rows = sqlx::query(
+        r#"
+    SELECT operator_id, avs_count, strategy_count, slashing_count,
+           last_slash_at, last_update_block_ts, strategies_incomplete
+    FROM operators_snapshot
+    WHERE operator_id = ANY($1)
+    ORDER BY operator_id ASC
+    "#,
+    )
+    .bind(&id_refs[..])
+    .fetch_all(pool)
+    .await?;
+
+    load_operators(pool, ops_rows).await
+}
+
+/// Rebuilds a page from the retained history: the operators sampled by the
+/// last succeeded ingest run at or before `as_of`. Pages are ordered like `from_db_adapt`, or by id
+/// after `cursor` like `from_db_adapt_after`.
+pub async fn from_db_history_adapt(
+    pool: &Pool<Postgres>,
+    first: i32,
+    skip: i32,
+    cursor: Option<&str>,
+    as_of: AsOf,
+) -> Result<UniformPage, sqlx::Error> {
+    let skip = if cursor.is_some() { 0 } else { skip };
+    let (operators, block) = load_history(pool, Some(first), skip, cursor, as_of).await?;
+    let next_cursor = match cursor {
+        Some(_) => next_cursor_for(
+            operators.len(),
+            operators.last().map(|o| o.operator_id.as_str()),
+            first,
+        ),
+        None => None,
+    };
+
+    Ok(UniformPage {
+        operators,
+        page_meta: PageMeta {
+            first,
+            skip,
+            next_cursor,
+            block,
+        },
+    })
+}
+
+pub async fn from_db_history_all(
+    pool: &Pool<Postgres>,
+    as_of: AsOf,
+) -> Result<UniformPage, sqlx::Error> {
+    let (operators, block) = load_history(pool, None, 0, None, as_of).await?;
+    let first = operators.len() as i32;
+
+    Ok(UniformPage {
+        operators,
+        page_meta: PageMeta {
+            first,
+            skip: 0,
+            next_cursor: None,
+            block,
+        },
+    })
+}
+
+/// Bounds on `(captured_at, block_number)` selecting the runs visible at
+/// `as_of`.
+fn as_of_bounds(as_of: AsOf) -> (Option<i64>, Option<i64>) {
+    match as_of {
+        AsOf::Timestamp(ts) => (Some(ts), None),
+        AsOf::Block(block) => (None, Some(block)),
+    }
+}
+
+/// Operators as captured by the last succeeded ingest run visible at `as_of`,
+/// plus the block that run read. Operators the run did not see are not
+/// returned, and samples of failed or unfinished runs are never read. A
+/// `None` limit returns every operator.
+async fn load_history(
+    pool: &Pool<Postgres>,
+    limit: Option<i32>,
+    skip: i32,
+    cursor: Option<&str>,
+    as_of: AsOf,
+) -> Result<(Vec<UniformOperator>, Option<i64>), sqlx::Error> {
+    let (max_ts, max_block) = as_of_bounds(as_of);
+
+    let _t = DbTimer::new("select_operator_history_as_of");
+    let ops_rows = sqlx::query(
+        r#"
+    WITH run AS (
+        SELECT h.run_id
+        FROM operator_history h
+        JOIN ingest_runs r ON r.run_id = h.run_id AND r.status = $6
+        WHERE ($1::BIGINT IS NULL OR h.captured_at <= $1)
+          AND ($2::BIGINT IS NULL OR h.block_number <= $2)
+        ORDER BY h.captured_at DESC
+        LIMIT 1
+    )
+    SELECT l.operator_id, l.captured_at, l.avs_count, l.strategy_count, l.slashing_count,
+           l.last_update_block_ts, l.block_number, l.positions_incomplete,
+           (SELECT MAX(s.block_timestamp) FROM operator_slashing s
+             WHERE s.operator_id = l.operator_id AND s.block_timestamp <= l.captured_at) AS last_slash_at
+    FROM operator_history l
+    JOIN run ON l.run_id = run.run_id
+    WHERE ($3::TEXT IS NULL OR l.operator_id > $3)
+    ORDER BY CASE WHEN $3::TEXT IS NULL THEN l.last_update_block_ts END DESC, l.operator_id ASC
+    LIMIT $4::BIGINT OFFSET $5
+    "#,
+    )
+    .bind(max_ts)
+    .bind(max_block)
+    .bind(cursor)
+    .bind(limit.map(i64::from))
+    .bind(skip as i64)
+    .bind(IngestStatus::Succeeded.as_str())
+    .fetch_all(pool)
+    .await?;
+
+    let op_ids: Vec<String> = ops_rows.iter().map(|r| r.get("operator_id")).collect();
+    let captured: Vec<i64> = ops_rows.iter().map(|r| r.get("captured_at")).collect();
+    let block = ops_rows
+        .iter()
+        .filter_map(|r| r.get::<Option<i64>, _>("block_number"))
+        .max();
+
+    let _t2 = DbTimer::new("select_operator_strategy_history_as_of");
+    let pos_rows = sqlx::query(
+        r#"
+    SELECT h.operator_id, h.strategy_id, h.token_id, h.token_symbol, h.token_decimals,
+           h.total_shares, h.exchange_rate
+    FROM operator_strategy_history h
+    JOIN UNNEST($1::TEXT[], $2::BIGINT[]) AS k(operator_id, captured_at)
+      ON h.operator_id = k.operator_id AND h.captured_at = k.captured_at
+    "#,
+    )
+    .bind(&op_ids[..])
+    .bind(&captured[..])
+    .fetch_all(pool)
+    .await?;
+
+    let mut pos_map = positions_by_operator(pos_rows);
+    let operators = ops_rows
+        .into_iter()
+        .map(|r| {
+            let operator_id: String = r.get("operator_id");
+            UniformOperator {
+                operator_id: operator_id.clone(),
+                avs_count: r.get::<i32, _>("avs_count"),
+                strategy_count: r.get::<i32, _>("strategy_count"),
+                slashing_count: r.get::<i32, _>("slashing_count"),
+                last_slash_at: r.get::<Option<i64>, _>("last_slash_at"),
+                last_update_block_ts: r.get::<i64, _>("last_update_block_ts"),
+                positions: pos_map.remove(&operator_id).unwrap_or_default(),
+                positions_incomplete: r.get::<bool, _>("positions_incomplete"),
+            }
+        })
+        .collect();
+
+    Ok((operators, block))
+}
+
+async fn load_operators(
+    pool: &Pool<Postgres>,
+    ops_rows: Vec<PgRow>,
+) -> Result<Vec<UniformOperator>, sqlx::Error> {
     if ops_rows.is_empty() {
-        return Ok(UniformPage {
-            operators: vec![],
-            page_meta: PageMeta { first, skip },
-        });
+        return Ok(vec![]);
     }
 
     let op_ids: Vec<String> = ops_rows
@@ -50,21 +362,7 @@ pub async fn from_db_adapt(
     .fetch_all(pool)
     .await?;
 
-    let mut pos_map: BTreeMap<String, Vec<UniformPosition>> = BTreeMap::new();
-    for r in pos_rows {
-        let operator_id: String = r.get("operator_id");
-        pos_map
-            .entry(operator_id)
-            .or_default()
-            .push(UniformPosition {
-                strategy_id: r.get("strategy_id"),
-                token_id: r.get("token_id"),
-                token_symbol: r.get("token_symbol"),
-                token_decimals: r.get::<i32, _>("token_decimals"),
-                total_shares: r.get("total_shares"),
-                exchange_rate: r.get("exchange_rate"),
-            });
-    }
+    let mut pos_map = positions_by_operator(pos_rows);
 
     let operators = ops_rows
         .into_iter()
@@ -78,12 +376,46 @@ pub async fn from_db_adapt(
                 last_slash_at: r.try_get::<i64, _>("last_slash_at").ok(),
                 last_update_block_ts: r.get::<i64, _>("last_update_block_ts"),
                 positions: pos_map.remove(&operator_id).unwrap_or_default(),
+                positions_incomplete: r.get::<bool, _>("strategies_incomplete"),
             }
         })
         .collect();
 
-    Ok(UniformPage {
-        operators,
-        page_meta: PageMeta { first, skip },
-    })
+    Ok(operators)
+}
+
+fn positions_by_operator(pos_rows: Vec<PgRow>) -> BTreeMap<String, Vec<UniformPosition>> {
+    let mut pos_map: BTreeMap<String, Vec<UniformPosition>> = BTreeMap::new();
+    for r in pos_rows {
+        let operator_id: String = r.get("operator_id");
+        pos_map
+            .entry(operator_id)
+            .or_default()
+            .push(UniformPosition {
+                strategy_id: r.get("strategy_id"),
+                token_id: r.get("token_id"),
+                token_symbol: r.get("token_symbol"),
+                token_decimals: r.get::<i32, _>("token_decimals"),
+                total_shares: r.get("total_shares"),
+                exchange_rate: r.get("exchange_rate"),
+            });
+    }
+    pos_map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn as_of_bounds_filter_on_one_axis() {
+        assert_eq!(
+            as_of_bounds(AsOf::Timestamp(1_700_000_000)),
+            (Some(1_700_000_000), None)
+        );
+        assert_eq!(
+            as_of_bounds(AsOf::Block(19_000_000)),
+            (None, Some(19_000_000))
+        );
+    }
 }
@@ -40,12 +40,18 @@ pub fn from_subgraph_adapt(page: &OperatorsSnapshotData, first: i32, skip: i32)
                 last_slash_at,
                 last_update_block_ts,
                 positions,
+                positions_incomplete: op.strategies_incomplete,
             }
         })
         .collect();
 
     UniformPage {
         operators,
-        page_meta: PageMeta { first, skip },
+        page_meta: PageMeta {
+            first,
+            skip,
+            next_cursor: page.next_cursor.clone(),
+            block: page.block_number(),
+        },
     }
 }
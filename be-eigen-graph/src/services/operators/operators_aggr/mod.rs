@@ -1,4 +1,6 @@
+pub mod aggregates_response;
 pub mod from_db_adapt;
 pub mod from_subgraph_adapt;
 pub mod operators_aggregator;
 pub mod operators_part;
+pub mod strategies_aggregator;
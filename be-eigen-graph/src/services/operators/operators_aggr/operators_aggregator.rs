@@ -3,9 +3,17 @@ use crate::models::operators_aggr::{
     TableRow,
 };
 use crate::models::operators_aggr::{UniformOperator, UniformPage};
+use crate::services::concentration::concentration_metrics::{
+    concentration_of, concentration_of_f64,
+};
+use crate::services::prices::price_book::PriceBook;
+use crate::services::valuation::position_value::{
+    NORMALIZED_DECIMALS, TokenAmount, format_units, position_value,
+};
 use num_bigint::BigUint;
 use num_traits::{ToPrimitive, Zero};
 use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 
 pub fn aggregate(
     page: &UniformPage,
@@ -38,6 +46,110 @@ pub fn aggregate(
     out
 }
 
+/// Fills `tvl_usd` on every slice and operator the price book can value and,
+/// when anything was priced, re-sorts by USD so cross-token ranking is
+/// meaningful. Operators without any priced slice sort last.
+pub fn apply_prices(aggr: &mut [OperatorAggregate], page: &UniformPage, prices: &PriceBook) {
+    if prices.is_empty() {
+        return;
+    }
+
+    let mut tokens: HashMap<(&str, &str), (&str, i32)> = HashMap::new();
+    for op in &page.operators {
+        for p in &op.positions {
+            tokens.insert(
+                (op.operator_id.as_str(), p.strategy_id.as_str()),
+                (p.token_symbol.as_str(), p.token_decimals),
+            );
+        }
+    }
+
+    for a in aggr.iter_mut() {
+        let mut total: Option<f64> = None;
+        for s in a.strategy_breakdown.iter_mut() {
+            let Some((symbol, decimals)) = tokens
+                .get(&(a.operator_id.as_str(), s.strategy_id.as_str()))
+                .copied()
+            else {
+                continue;
+            };
+            let Some(atomic) = parse_u256(&s.tvl_atomic).ok() else {
+                continue;
+            };
+            let amount = TokenAmount {
+                atomic,
+                decimals: decimals.max(0) as u32,
+            };
+            s.tvl_usd = prices.usd_value(symbol, &amount);
+            if let Some(usd) = s.tvl_usd {
+                *total.get_or_insert(0.0) += usd;
+            }
+        }
+        a.tvl_usd = total;
+        if let Some(total) = fully_priced_tvl_usd(a) {
+            weigh_by_usd(a, total);
+        }
+    }
+
+    aggr.sort_by(|a, b| match (a.tvl_usd, b.tvl_usd) {
+        (Some(x), Some(y)) => y
+            .partial_cmp(&x)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.operator_id.cmp(&b.operator_id)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+}
+
+/// Recomputes strategy shares and concentration from USD values, which are
+/// comparable across tokens, and orders the slices by them.
+fn weigh_by_usd(a: &mut OperatorAggregate, total: f64) {
+    let usd = |s: &StrategySlice| s.tvl_usd.unwrap_or(0.0);
+    a.strategy_breakdown.sort_by(|x, y| {
+        usd(y)
+            .partial_cmp(&usd(x))
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| x.strategy_id.cmp(&y.strategy_id))
+    });
+    for s in a.strategy_breakdown.iter_mut() {
+        s.share = Some(if total > 0.0 { usd(s) / total } else { 0.0 });
+    }
+    let weights: Vec<f64> = a.strategy_breakdown.iter().map(usd).collect();
+    let concentration = concentration_of_f64(&weights);
+    a.hhi_strategy = Some(concentration.hhi);
+    a.top_strategy_share = Some(
+        a.strategy_breakdown
+            .first()
+            .and_then(|s| s.share)
+            .unwrap_or(0.0),
+    );
+    a.concentration = Some(concentration);
+}
+
+/// USD TVL of an operator whose every non-empty slice is priced. A partial sum
+/// would weigh the operator by whichever tokens happen to have a price.
+pub fn fully_priced_tvl_usd(a: &OperatorAggregate) -> Option<f64> {
+    let all_priced = a
+        .strategy_breakdown
+        .iter()
+        .all(|s| s.tvl_usd.is_some() || s.tvl_atomic == "0");
+    if all_priced {
+        Some(a.tvl_usd.unwrap_or(0.0))
+    } else {
+        None
+    }
+}
+
+/// Sums slice TVLs in the token's own atomic units; only meaningful for a
+/// single-token page such as one produced by `partition_by_token`.
+pub fn token_tvl_atomic(aggr: &[OperatorAggregate]) -> BigUint {
+    aggr.iter()
+        .flat_map(|a| a.strategy_breakdown.iter())
+        .filter_map(|s| parse_u256(&s.tvl_atomic).ok())
+        .fold(BigUint::zero(), |acc, v| acc + v)
+}
+
 pub fn to_table_rows(aggr: &[OperatorAggregate]) -> Vec<TableRow> {
     aggr.iter()
         .map(|a| TableRow {
@@ -48,8 +160,12 @@ pub fn to_table_rows(aggr: &[OperatorAggregate]) -> Vec<TableRow> {
             last_slash_at: a.last_slash_at,
             last_update_block_ts: a.last_update_block_ts,
             tvl_total_atomic: a.tvl_total_atomic.clone(),
+            tvl_total_decimal: a.tvl_total_decimal.clone(),
+            tvl_usd: a.tvl_usd,
             hhi_strategy: a.hhi_strategy,
+            concentration: a.concentration.clone(),
             nonzero_strategy_count: a.nonzero_strategy_count,
+            positions_incomplete: a.positions_incomplete,
         })
         .collect()
 }
@@ -61,6 +177,8 @@ pub fn to_bar_series(aggr_sorted: &[OperatorAggregate], top_n: usize) -> Vec<Bar
         .map(|a| BarItem {
             operator_id: a.operator_id.clone(),
             tvl_total_atomic: a.tvl_total_atomic.clone(),
+            tvl_total_decimal: a.tvl_total_decimal.clone(),
+            tvl_usd: a.tvl_usd,
         })
         .collect()
 }
@@ -106,7 +224,7 @@ pub fn detect_outliers(
 ) -> Outliers {
     let high_concentration = aggr
         .iter()
-        .filter(|a| a.hhi_strategy >= hhi_threshold)
+        .filter(|a| a.hhi_strategy.is_some_and(|h| h >= hhi_threshold))
         .map(|a| a.operator_id.clone())
         .collect();
 
@@ -130,65 +248,72 @@ pub fn detect_outliers(
     }
 }
 
+/// Strategy shares and concentration weigh slices by amount only when all of
+/// the operator's holdings are in one token; amounts of different tokens
+/// cannot be added, so a multi-token operator gets them from `apply_prices`
+/// once every slice is priced, and `None` until then.
 fn aggregate_one(op: &UniformOperator) -> OperatorAggregate {
-    let mut slices: Vec<(String, BigUint)> = Vec::with_capacity(op.positions.len());
+    let mut slices: Vec<(String, TokenAmount)> = Vec::with_capacity(op.positions.len());
     let mut zero_share_flag = false;
+    let held_tokens: HashSet<&str> = op
+        .positions
+        .iter()
+        .filter(|p| p.total_shares != "0")
+        .map(|p| p.token_id.as_str())
+        .collect();
+    let single_token = held_tokens.len() <= 1;
 
     for p in &op.positions {
         if p.total_shares == "0" {
             zero_share_flag = true;
         }
 
-        let rate = if p.exchange_rate.is_empty() || p.exchange_rate == "0" {
-            "1000000000000000000"
-        } else {
-            &p.exchange_rate
-        };
-
-        let tvl = mul_u256(&p.total_shares, rate).unwrap_or_else(|_| BigUint::zero());
+        let tvl = position_value(&p.total_shares, &p.exchange_rate, p.token_decimals);
         slices.push((p.strategy_id.clone(), tvl));
     }
 
-    let total: BigUint = slices.iter().fold(BigUint::zero(), |acc, (_, v)| acc + v);
+    let total: BigUint = slices
+        .iter()
+        .fold(BigUint::zero(), |acc, (_, v)| acc + v.normalized());
 
     let total_f = to_f64(&total);
-    let breakdown: Vec<StrategySlice> = if total.is_zero() || total_f == 0.0 {
-        slices
-            .into_iter()
-            .map(|(sid, amt)| StrategySlice {
+    let mut breakdown: Vec<(BigUint, StrategySlice)> = slices
+        .into_iter()
+        .map(|(sid, amt)| {
+            let normalized = amt.normalized();
+            let share = if !single_token {
+                None
+            } else if total.is_zero() || total_f == 0.0 {
+                Some(0.0)
+            } else {
+                Some(to_f64(&normalized) / total_f)
+            };
+            let slice = StrategySlice {
                 strategy_id: sid,
-                tvl_atomic: amt.to_string(),
-                share: 0.0,
-            })
-            .collect()
-    } else {
-        let mut v: Vec<StrategySlice> = slices
-            .into_iter()
-            .map(|(sid, amt)| {
-                let share = to_f64(&amt) / total_f;
-                StrategySlice {
-                    strategy_id: sid,
-                    tvl_atomic: amt.to_string(),
-                    share,
-                }
-            })
-            .collect();
-
-        v.sort_by(|a, b| {
-            let aa = parse_u256(&a.tvl_atomic).unwrap_or_else(|_| BigUint::zero());
-            let bb = parse_u256(&b.tvl_atomic).unwrap_or_else(|_| BigUint::zero());
-            match bb.cmp(&aa) {
-                Ordering::Equal => a.strategy_id.cmp(&b.strategy_id),
-                o => o,
-            }
+                tvl_atomic: amt.atomic_string(),
+                tvl_decimal: amt.decimal_string(),
+                tvl_usd: None,
+                share,
+            };
+            (normalized, slice)
+        })
+        .collect();
+
+    if !total.is_zero() && total_f != 0.0 {
+        breakdown.sort_by(|(aa, a), (bb, b)| match bb.cmp(aa) {
+            Ordering::Equal => a.strategy_id.cmp(&b.strategy_id),
+            o => o,
         });
-        v
-    };
+    }
+    let (weights, breakdown): (Vec<BigUint>, Vec<StrategySlice>) = breakdown.into_iter().unzip();
 
-    let top_strategy_share = breakdown.first().map(|s| s.share).unwrap_or(0.0);
-    let hhi_strategy = breakdown
-        .iter()
-        .fold(0.0_f64, |acc, s| acc + s.share * s.share);
+    let concentration = single_token.then(|| concentration_of(&weights));
+    let top_strategy_share = if single_token {
+        Some(breakdown.first().and_then(|s| s.share).unwrap_or(0.0))
+    } else {
+        None
+    };
+    let hhi_strategy = concentration.as_ref().map(|c| c.hhi);
 
     OperatorAggregate {
         operator_id: op.operator_id.clone(),
@@ -199,6 +324,8 @@ fn aggregate_one(op: &UniformOperator) -> OperatorAggregate {
         last_update_block_ts: op.last_update_block_ts,
 
         tvl_total_atomic: total.to_string(),
+        tvl_total_decimal: format_units(&total, NORMALIZED_DECIMALS),
+        tvl_usd: None,
         nonzero_strategy_count: op
             .positions
             .iter()
@@ -208,7 +335,9 @@ fn aggregate_one(op: &UniformOperator) -> OperatorAggregate {
         strategy_breakdown: breakdown,
         top_strategy_share,
         hhi_strategy,
+        concentration,
         zero_share_flag,
+        positions_incomplete: op.positions_incomplete,
     }
 }
 
@@ -216,12 +345,84 @@ fn parse_u256(s: &str) -> Result<BigUint, ()> {
     BigUint::parse_bytes(s.as_bytes(), 10).ok_or(())
 }
 
-fn mul_u256(a: &str, b: &str) -> Result<BigUint, ()> {
-    let x = parse_u256(a)?;
-    let y = parse_u256(b)?;
-    Ok(x * y)
-}
-
 fn to_f64(x: &BigUint) -> f64 {
     x.to_f64().unwrap_or(0.0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::operators_aggr::{PageMeta, UniformPosition};
+    use std::collections::BTreeMap;
+
+    const E18: &str = "000000000000000000";
+
+    fn page(positions: &[(&str, &str, &str)]) -> UniformPage {
+        UniformPage {
+            operators: vec![UniformOperator {
+                operator_id: "0xop".to_string(),
+                avs_count: 1,
+                strategy_count: positions.len() as i32,
+                slashing_count: 0,
+                last_slash_at: None,
+                last_update_block_ts: 0,
+                positions: positions
+                    .iter()
+                    .map(|(strategy, symbol, whole)| UniformPosition {
+                        strategy_id: strategy.to_string(),
+                        token_id: format!("token-{symbol}"),
+                        token_symbol: symbol.to_string(),
+                        token_decimals: 18,
+                        total_shares: format!("{whole}{E18}"),
+                        exchange_rate: String::new(),
+                    })
+                    .collect(),
+                positions_incomplete: false,
+            }],
+            page_meta: PageMeta {
+                first: 0,
+                skip: 0,
+                next_cursor: None,
+                block: None,
+            },
+        }
+    }
+
+    #[test]
+    fn single_token_operator_is_weighted_by_amount() {
+        let p = page(&[("s1", "EIGEN", "3"), ("s2", "EIGEN", "1")]);
+        let a = &aggregate(&p, &AggregatorParams::default(), 0)[0];
+        assert_eq!(a.hhi_strategy, Some(0.625));
+        assert_eq!(a.top_strategy_share, Some(0.75));
+        assert_eq!(a.strategy_breakdown[0].share, Some(0.75));
+    }
+
+    #[test]
+    fn unpriced_multi_token_operator_has_no_concentration() {
+        let p = page(&[("s1", "EIGEN", "3000"), ("s2", "WETH", "1")]);
+        let mut aggr = aggregate(&p, &AggregatorParams::default(), 0);
+        let prices = PriceBook::new(BTreeMap::from([("WETH".to_string(), 3000.0)]));
+        apply_prices(&mut aggr, &p, &prices);
+        assert!(aggr[0].hhi_strategy.is_none());
+        assert!(aggr[0].concentration.is_none());
+        assert!(aggr[0].strategy_breakdown.iter().all(|s| s.share.is_none()));
+        let outliers = detect_outliers(&aggr, 0.0, 0, 0);
+        assert!(outliers.high_concentration.is_empty());
+    }
+
+    #[test]
+    fn priced_multi_token_operator_is_weighted_by_usd() {
+        let p = page(&[("s1", "EIGEN", "3000"), ("s2", "WETH", "1")]);
+        let mut aggr = aggregate(&p, &AggregatorParams::default(), 0);
+        let prices = PriceBook::new(BTreeMap::from([
+            ("WETH".to_string(), 3000.0),
+            ("EIGEN".to_string(), 1.0),
+        ]));
+        apply_prices(&mut aggr, &p, &prices);
+        // 3000 EIGEN and 1 WETH are worth the same
+        let a = &aggr[0];
+        assert!((a.hhi_strategy.unwrap() - 0.5).abs() < 1e-9);
+        assert!((a.top_strategy_share.unwrap() - 0.5).abs() < 1e-9);
+        assert_eq!(a.concentration.as_ref().unwrap().nakamoto, 2);
+    }
+}
@@ -2,18 +2,29 @@ use crate::models::operators_aggr::{PageMeta, UniformOperator, UniformPage, Unif
 use std::collections::BTreeMap;
 
 pub fn partition_by_token(page: &UniformPage) -> BTreeMap<String, UniformPage> {
+    partition_by(page, |p| &p.token_symbol)
+}
+
+/// Like `partition_by_token`, but keyed by token address, so distinct tokens
+/// that share a symbol stay apart.
+pub fn partition_by_token_id(page: &UniformPage) -> BTreeMap<String, UniformPage> {
+    partition_by(page, |p| &p.token_id)
+}
+
+fn partition_by(
+    page: &UniformPage,
+    key: impl Fn(&UniformPosition) -> &String,
+) -> BTreeMap<String, UniformPage> {
     let mut out: BTreeMap<String, Vec<UniformOperator>> = BTreeMap::new();
 
     for op in &page.operators {
         let mut buckets: BTreeMap<String, Vec<UniformPosition>> = BTreeMap::new();
         for p in &op.positions {
-            if p.token_symbol.is_empty() {
+            let k = key(p);
+            if k.is_empty() {
                 continue;
             }
-            buckets
-                .entry(p.token_symbol.clone())
-                .or_default()
-                .push(p.clone());
+            buckets.entry(k.clone()).or_default().push(p.clone());
         }
 
         for (sym, positions) in buckets {
@@ -33,6 +44,8 @@ pub fn partition_by_token(page: &UniformPage) -> BTreeMap<String, UniformPage> {
                 page_meta: PageMeta {
                     first: page.page_meta.first,
                     skip: page.page_meta.skip,
+                    next_cursor: page.page_meta.next_cursor.clone(),
+                    block: page.page_meta.block,
                 },
             };
             (sym, token_page)
@@ -0,0 +1,105 @@
+use crate::models::operators_aggr::{
+    AggregatorParams, OperatorSlice, StrategyAggregate, UniformPage, UniformPosition,
+};
+use crate::services::concentration::concentration_metrics::concentration_of;
+use crate::services::operators::operators_aggr::operators_aggregator;
+use crate::services::valuation::position_value::format_units;
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// Inverts the operator-centric aggregates: every strategy gets the TVL each
+/// operator holds in it, and concentration is measured across operators.
+pub fn aggregate_strategies(page: &UniformPage, now_ts: i64) -> Vec<StrategyAggregate> {
+    let params = AggregatorParams::default();
+    let aggr = operators_aggregator::aggregate(page, &params, now_ts);
+
+    let mut positions: BTreeMap<&str, &UniformPosition> = BTreeMap::new();
+    for op in &page.operators {
+        for p in &op.positions {
+            positions.entry(p.strategy_id.as_str()).or_insert(p);
+        }
+    }
+
+    let mut holdings: BTreeMap<&str, Vec<(String, BigUint)>> = BTreeMap::new();
+    for a in &aggr {
+        for s in &a.strategy_breakdown {
+            let tvl = BigUint::parse_bytes(s.tvl_atomic.as_bytes(), 10).unwrap_or_default();
+            holdings
+                .entry(s.strategy_id.as_str())
+                .or_default()
+                .push((a.operator_id.clone(), tvl));
+        }
+    }
+
+    let mut out: Vec<StrategyAggregate> = holdings
+        .into_iter()
+        .filter_map(|(strategy_id, slices)| {
+            let p = positions.get(strategy_id)?;
+            Some(aggregate_one(p, slices))
+        })
+        .collect();
+
+    out.sort_by(|a, b| {
+        let at = BigUint::parse_bytes(a.tvl_total_atomic.as_bytes(), 10).unwrap_or_default();
+        let bt = BigUint::parse_bytes(b.tvl_total_atomic.as_bytes(), 10).unwrap_or_default();
+        match bt.cmp(&at) {
+            Ordering::Equal => a.strategy_id.cmp(&b.strategy_id),
+            o => o,
+        }
+    });
+
+    out
+}
+
+fn aggregate_one(p: &UniformPosition, mut slices: Vec<(String, BigUint)>) -> StrategyAggregate {
+    slices.sort_by(|(ai, av), (bi, bv)| match bv.cmp(av) {
+        Ordering::Equal => ai.cmp(bi),
+        o => o,
+    });
+
+    let decimals = p.token_decimals.max(0) as u32;
+    let total: BigUint = slices.iter().fold(BigUint::zero(), |acc, (_, v)| acc + v);
+    let total_f = total.to_f64().unwrap_or(0.0);
+    let operator_count = slices.len() as i32;
+    let nonzero_operator_count = slices.iter().filter(|(_, v)| !v.is_zero()).count() as i32;
+    let weights: Vec<BigUint> = slices.iter().map(|(_, v)| v.clone()).collect();
+    let concentration = concentration_of(&weights);
+
+    let breakdown: Vec<OperatorSlice> = slices
+        .into_iter()
+        .map(|(operator_id, amt)| {
+            let share = if total_f == 0.0 {
+                0.0
+            } else {
+                amt.to_f64().unwrap_or(0.0) / total_f
+            };
+            OperatorSlice {
+                operator_id,
+                tvl_atomic: amt.to_string(),
+                tvl_decimal: format_units(&amt, decimals),
+                share,
+            }
+        })
+        .collect();
+
+    let top_operator_share = breakdown.first().map(|s| s.share).unwrap_or(0.0);
+    let hhi_operator = concentration.hhi;
+
+    StrategyAggregate {
+        strategy_id: p.strategy_id.clone(),
+        token_id: p.token_id.clone(),
+        token_symbol: p.token_symbol.clone(),
+        token_decimals: p.token_decimals,
+        exchange_rate: p.exchange_rate.clone(),
+        tvl_total_atomic: total.to_string(),
+        tvl_total_decimal: format_units(&total, decimals),
+        operator_count,
+        nonzero_operator_count,
+        operator_breakdown: breakdown,
+        top_operator_share,
+        hhi_operator,
+        concentration,
+    }
+}
@@ -1,31 +1,48 @@
-use crate::models::cached::{Cached, DataSource};
+use crate::api::subgraph::client::SubgraphClient;
+use crate::api::subgraph::errors::InfraError;
+use crate::caching::backend::{CacheTtl, LayeredCache};
+use crate::models::cached::Cached;
 use crate::models::operators_aggr::UniformPage;
-use crate::models::operators_snapshot::{OperatorOrderBy, OperatorsSnapshotVars, OrderDirection};
+use crate::models::operators_snapshot::{
+    BlockHeight, OperatorOrderBy, OperatorsSnapshotCursorVars, OperatorsSnapshotVars,
+    OrderDirection,
+};
 use crate::services::operators::operators_aggr::from_subgraph_adapt::from_subgraph_adapt;
-use crate::services::operators::operators_snapshot_cached::operators_snapshot_cached;
-use redis::aio::ConnectionManager;
-use reqwest::{Client, Url};
+use crate::services::operators::operators_snapshot_cached::{
+    operators_snapshot_after_cached, operators_snapshot_cached,
+};
+use std::sync::Arc;
 
 pub async fn uniform_page_from_subgraph_cached(
-    client: Client,
-    endpoint: Url,
+    client: &SubgraphClient,
     first: i32,
     skip: i32,
-    redis: &Option<ConnectionManager>,
-    ttl_secs: u64,
-) -> Result<Cached<UniformPage>, anyhow::Error> {
-    let vars = OperatorsSnapshotVars {
-        first,
-        skip,
-        order_by: OperatorOrderBy::LastUpdateBlockTimestamp,
-        order_direction: OrderDirection::Desc,
-        has_slashing: 0,
+    cursor: Option<String>,
+    block: Option<BlockHeight>,
+    cache: &Arc<LayeredCache>,
+    ttl: CacheTtl,
+) -> Result<Cached<UniformPage>, InfraError> {
+    let cached = match cursor {
+        Some(cursor) => {
+            let vars = OperatorsSnapshotCursorVars {
+                first,
+                cursor,
+                has_slashing: 0,
+                block,
+            };
+            operators_snapshot_after_cached(client, vars, cache, ttl).await?
+        }
+        None => {
+            let vars = OperatorsSnapshotVars {
+                first,
+                skip,
+                order_by: OperatorOrderBy::LastUpdateBlockTimestamp,
+                order_direction: OrderDirection::Desc,
+                has_slashing: 0,
+                block,
+            };
+            operators_snapshot_cached(client, vars, cache, ttl).await?
+        }
     };
-    let cached = operators_snapshot_cached(client, endpoint, vars, redis, ttl_secs).await?;
-    let page: UniformPage = from_subgraph_adapt(&cached.data, first, skip);
-    let source = match cached.source {
-        crate::models::cached::DataSource::Redis => DataSource::Redis,
-        _ => DataSource::Subgraph,
-    };
-    Ok(Cached { source, data: page })
+    Ok(cached.map(|data| from_subgraph_adapt(&data, first, skip)))
 }
@@ -0,0 +1,67 @@
+const CURSOR_PREFIX: &str = "op:";
+
+pub fn encode_cursor(last_operator_id: &str) -> String {
+    format!("{CURSOR_PREFIX}{last_operator_id}")
+        .bytes()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+pub fn decode_cursor(cursor: &str) -> Option<String> {
+    if cursor.is_empty() {
+        return Some(String::new());
+    }
+    if !cursor.len().is_multiple_of(2) {
+        return None;
+    }
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(cursor.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    let raw = String::from_utf8(bytes).ok()?;
+    raw.strip_prefix(CURSOR_PREFIX).map(str::to_string)
+}
+
+pub fn next_cursor_for(page_len: usize, last_id: Option<&str>, first: i32) -> Option<String> {
+    if page_len < first.max(1) as usize {
+        return None;
+    }
+    last_id.map(encode_cursor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_round_trips() {
+        let cursor = encode_cursor("0xAbC123");
+        assert!(cursor.bytes().all(|b| b.is_ascii_hexdigit()));
+        assert_eq!(decode_cursor(&cursor).as_deref(), Some("0xAbC123"));
+    }
+
+    #[test]
+    fn empty_cursor_starts_from_the_beginning() {
+        assert_eq!(decode_cursor("").as_deref(), Some(""));
+    }
+
+    #[test]
+    fn malformed_cursors_are_rejected() {
+        assert!(decode_cursor("abc").is_none());
+        assert!(decode_cursor("zz").is_none());
+        // valid hex, but without the cursor prefix
+        assert!(decode_cursor("3078").is_none());
+        // non-ASCII input must not panic on a char boundary
+        assert!(decode_cursor("aé0").is_none());
+    }
+
+    #[test]
+    fn next_cursor_only_for_full_pages() {
+        assert_eq!(next_cursor_for(2, Some("0xb"), 3), None);
+        assert_eq!(
+            next_cursor_for(3, Some("0xb"), 3),
+            Some(encode_cursor("0xb"))
+        );
+        assert_eq!(next_cursor_for(3, None, 3), None);
+    }
+}
@@ -0,0 +1,433 @@
+use crate::errors::ApiError;
+use crate::models::operators_aggr::{AggregatorParams, OperatorAggregate, UniformPage};
+use crate::models::operators_diff::{
+    AmountDelta, CountDelta, Mover, OperatorDelta, OperatorsDiff, RatioDelta, StrategyDelta,
+    UsdDelta,
+};
+use crate::models::operators_snapshot::AsOf;
+use crate::repositories::operator_history::select_succeeded_capture_at;
+use crate::services::operators::operators_aggr::from_db_adapt::{
+    from_db_adapt_all, from_db_history_all,
+};
+use crate::services::operators::operators_aggr::operators_aggregator::{
+    aggregate, apply_prices, fully_priced_tvl_usd, to_table_rows,
+};
+use crate::services::operators::operators_aggr::operators_part::partition_by_token_id;
+use crate::services::prices::price_book::PriceBook;
+use crate::services::valuation::position_value::{NORMALIZED_DECIMALS, format_units};
+use num_bigint::{BigInt, BigUint, Sign};
+use sqlx::{Pool, Postgres};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// One side of a diff: the history retained at a timestamp, or the current
+/// mirror.
+#[derive(Debug, Clone, Copy)]
+pub enum DiffPoint {
+    Stored(i64),
+    Live,
+}
+
+pub struct DiffSide {
+    pub page: UniformPage,
+    pub source: &'static str,
+    /// Sample time of the ingest run a stored side was read from.
+    pub captured_at: Option<i64>,
+}
+
+/// Both sides are read from the DB so that they were captured the same way.
+/// A stored side is the last succeeded ingest run at or before the
+/// timestamp, so a failed or interrupted run never shows up as removed
+/// operators; a timestamp before any such run is a 404.
+pub async fn load_diff_side(pool: &Pool<Postgres>, point: DiffPoint) -> Result<DiffSide, ApiError> {
+    Ok(match point {
+        DiffPoint::Stored(ts) => {
+            let Some(captured_at) = select_succeeded_capture_at(pool, ts).await? else {
+                return Err(ApiError::NotFound(format!(
+                    "no succeeded ingest run at or before {ts}"
+                )));
+            };
+            DiffSide {
+                page: from_db_history_all(pool, AsOf::Timestamp(captured_at)).await?,
+                source: "db",
+                captured_at: Some(captured_at),
+            }
+        }
+        DiffPoint::Live => DiffSide {
+            page: from_db_adapt_all(pool).await?,
+            source: "db",
+            captured_at: None,
+        },
+    })
+}
+
+/// How `movers` are ranked: by USD value across tokens, by amount within one.
+#[derive(Clone, Copy)]
+enum MoverRank<'a> {
+    Usd(&'a PriceBook),
+    Amount,
+}
+
+pub fn diff_pages(
+    from: &UniformPage,
+    to: &UniformPage,
+    prices: &PriceBook,
+    top_n: usize,
+    now_ts: i64,
+) -> OperatorsDiff {
+    diff_ranked(from, to, MoverRank::Usd(prices), top_n, now_ts)
+}
+
+fn diff_ranked(
+    from: &UniformPage,
+    to: &UniformPage,
+    rank: MoverRank<'_>,
+    top_n: usize,
+    now_ts: i64,
+) -> OperatorsDiff {
+    let params = AggregatorParams::default();
+    let mut before = aggregate(from, &params, now_ts);
+    let mut after = aggregate(to, &params, now_ts);
+    if let MoverRank::Usd(prices) = rank {
+        apply_prices(&mut before, from, prices);
+        apply_prices(&mut after, to, prices);
+    }
+
+    let before_by_id: HashMap<&str, &OperatorAggregate> =
+        before.iter().map(|a| (a.operator_id.as_str(), a)).collect();
+    let after_ids: BTreeSet<&str> = after.iter().map(|a| a.operator_id.as_str()).collect();
+
+    let token_meta = strategy_tokens(from, to);
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    for a in &after {
+        match before_by_id.get(a.operator_id.as_str()) {
+            None => added.push(a.clone()),
+            Some(b) => {
+                if let Some(delta) = operator_delta(b, a, &token_meta) {
+                    changed.push(delta);
+                }
+            }
+        }
+    }
+    let removed: Vec<OperatorAggregate> = before
+        .iter()
+        .filter(|b| !after_ids.contains(b.operator_id.as_str()))
+        .cloned()
+        .collect();
+
+    let slashed = changed
+        .iter()
+        .filter(|d| d.slashing_count.delta > 0)
+        .map(|d| d.operator_id.clone())
+        .collect();
+
+    let after_by_id: HashMap<&str, &OperatorAggregate> =
+        after.iter().map(|a| (a.operator_id.as_str(), a)).collect();
+    let mut movers = match rank {
+        MoverRank::Usd(_) => usd_movers(&changed, &before_by_id, &after_by_id),
+        MoverRank::Amount => amount_movers(&changed),
+    };
+    movers.truncate(top_n);
+
+    OperatorsDiff {
+        added: to_table_rows(&added),
+        removed: to_table_rows(&removed),
+        changed,
+        slashed,
+        movers,
+    }
+}
+
+fn amount_movers(changed: &[OperatorDelta]) -> Vec<Mover> {
+    let mut movers: Vec<(BigUint, Mover)> = changed
+        .iter()
+        .map(|d| {
+            let magnitude = signed(&d.tvl.delta_atomic).magnitude().clone();
+            let mover = Mover {
+                operator_id: d.operator_id.clone(),
+                tvl: d.tvl.clone(),
+                tvl_usd: None,
+            };
+            (magnitude, mover)
+        })
+        .filter(|(m, _)| m.bits() > 0)
+        .collect();
+    movers.sort_by(|(am, a), (bm, b)| bm.cmp(am).then_with(|| a.operator_id.cmp(&b.operator_id)));
+    movers.into_iter().map(|(_, m)| m).collect()
+}
+
+fn usd_movers(
+    changed: &[OperatorDelta],
+    before: &HashMap<&str, &OperatorAggregate>,
+    after: &HashMap<&str, &OperatorAggregate>,
+) -> Vec<Mover> {
+    let mut movers: Vec<Mover> = changed
+        .iter()
+        .filter_map(|d| {
+            let from = fully_priced_tvl_usd(before.get(d.operator_id.as_str())?)?;
+            let to = fully_priced_tvl_usd(after.get(d.operator_id.as_str())?)?;
+            Some(Mover {
+                operator_id: d.operator_id.clone(),
+                tvl: d.tvl.clone(),
+                tvl_usd: Some(UsdDelta {
+                    from,
+                    to,
+                    delta: to - from,
+                }),
+            })
+        })
+        .filter(|m| m.tvl_usd.as_ref().is_some_and(|u| u.delta != 0.0))
+        .collect();
+    movers.sort_by(|a, b| {
+        let (au, bu) = (abs_usd_delta(a), abs_usd_delta(b));
+        bu.total_cmp(&au)
+            .then_with(|| a.operator_id.cmp(&b.operator_id))
+    });
+    movers
+}
+
+fn abs_usd_delta(m: &Mover) -> f64 {
+    m.tvl_usd.as_ref().map_or(0.0, |u| u.delta.abs())
+}
+
+/// The same diff restricted to each token, keyed by token address so that
+/// distinct tokens sharing a symbol are not netted against each other.
+pub fn diff_by_token(
+    from: &UniformPage,
+    to: &UniformPage,
+    top_n: usize,
+    now_ts: i64,
+) -> BTreeMap<String, OperatorsDiff> {
+    let mut before = partition_by_token_id(from);
+    let mut after = partition_by_token_id(to);
+    let tokens: BTreeSet<String> = before.keys().chain(after.keys()).cloned().collect();
+
+    tokens
+        .into_iter()
+        .map(|token| {
+            let b = before.remove(&token).unwrap_or_else(|| empty_like(from));
+            let a = after.remove(&token).unwrap_or_else(|| empty_like(to));
+            let diff = diff_ranked(&b, &a, MoverRank::Amount, top_n, now_ts);
+            (token, diff)
+        })
+        .collect()
+}
+
+fn operator_delta(
+    b: &OperatorAggregate,
+    a: &OperatorAggregate,
+    token_meta: &HashMap<String, (String, u32)>,
+) -> Option<OperatorDelta> {
+    let strategies = strategy_deltas(b, a, token_meta);
+    let tvl = amount_delta(
+        &b.tvl_total_atomic,
+        &a.tvl_total_atomic,
+        NORMALIZED_DECIMALS,
+    );
+    let unchanged = strategies.is_empty()
+        && b.tvl_total_atomic == a.tvl_total_atomic
+        && b.avs_count == a.avs_count
+        && b.strategy_count == a.strategy_count
+        && b.slashing_count == a.slashing_count
+        && b.nonzero_strategy_count == a.nonzero_strategy_count;
+    if unchanged {
+        return None;
+    }
+
+    Some(OperatorDelta {
+        operator_id: a.operator_id.clone(),
+        avs_count: count_delta(b.avs_count, a.avs_count),
+        strategy_count: count_delta(b.strategy_count, a.strategy_count),
+        slashing_count: count_delta(b.slashing_count, a.slashing_count),
+        nonzero_strategy_count: count_delta(b.nonzero_strategy_count, a.nonzero_strategy_count),
+        last_slash_at: a.last_slash_at,
+        tvl,
+        hhi_strategy: ratio_delta(b.hhi_strategy, a.hhi_strategy),
+        top_strategy_share: ratio_delta(b.top_strategy_share, a.top_strategy_share),
+        strategies,
+    })
+}
+
+fn strategy_deltas(
+    b: &OperatorAggregate,
+    a: &OperatorAggregate,
+    token_meta: &HashMap<String, (String, u32)>,
+) -> Vec<StrategyDelta> {
+    let before: HashMap<&str, &str> = b
+        .strategy_breakdown
+        .iter()
+        .map(|s| (s.strategy_id.as_str(), s.tvl_atomic.as_str()))
+        .collect();
+    let after: HashMap<&str, &str> = a
+        .strategy_breakdown
+        .iter()
+        .map(|s| (s.strategy_id.as_str(), s.tvl_atomic.as_str()))
+        .collect();
+    let ids: BTreeSet<&str> = before.keys().chain(after.keys()).copied().collect();
+
+    ids.into_iter()
+        .filter_map(|id| {
+            let from = before.get(id).copied().unwrap_or("0");
+            let to = after.get(id).copied().unwrap_or("0");
+            if from == to {
+                return None;
+            }
+            let (symbol, decimals) = token_meta
+                .get(id)
+                .cloned()
+                .unwrap_or_else(|| (String::new(), NORMALIZED_DECIMALS));
+            Some(StrategyDelta {
+                strategy_id: id.to_string(),
+                token_symbol: symbol,
+                tvl: amount_delta(from, to, decimals),
+            })
+        })
+        .collect()
+}
+
+fn strategy_tokens(from: &UniformPage, to: &UniformPage) -> HashMap<String, (String, u32)> {
+    from.operators
+        .iter()
+        .chain(&to.operators)
+        .flat_map(|o| &o.positions)
+        .map(|p| {
+            (
+                p.strategy_id.clone(),
+                (p.token_symbol.clone(), p.token_decimals.max(0) as u32),
+            )
+        })
+        .collect()
+}
+
+fn amount_delta(from: &str, to: &str, decimals: u32) -> AmountDelta {
+    let delta = signed(to) - signed(from);
+    let sign = if delta.sign() == Sign::Minus { "-" } else { "" };
+    AmountDelta {
+        from_atomic: from.to_string(),
+        to_atomic: to.to_string(),
+        delta_atomic: delta.to_string(),
+        delta_decimal: format!("{sign}{}", format_units(delta.magnitude(), decimals)),
+    }
+}
+
+fn signed(s: &str) -> BigInt {
+    BigInt::parse_bytes(s.as_bytes(), 10).unwrap_or_default()
+}
+
+fn count_delta(from: i32, to: i32) -> CountDelta {
+    CountDelta {
+        from,
+        to,
+        delta: to - from,
+    }
+}
+
+/// `None` unless both sides could be measured.
+fn ratio_delta(from: Option<f64>, to: Option<f64>) -> Option<RatioDelta> {
+    let (from, to) = from.zip(to)?;
+    Some(RatioDelta {
+        from,
+        to,
+        delta: to - from,
+    })
+}
+
+fn empty_like(page: &UniformPage) -> UniformPage {
+    UniformPage {
+        operators: Vec::new(),
+        page_meta: page.page_meta.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::operators_aggr::{PageMeta, UniformOperator, UniformPosition};
+
+    const E18: &str = "000000000000000000";
+
+    fn operator(id: &str, strategy: &str, symbol: &str, whole: u32) -> UniformOperator {
+        UniformOperator {
+            operator_id: id.to_string(),
+            avs_count: 1,
+            strategy_count: 1,
+            slashing_count: 0,
+            last_slash_at: None,
+            last_update_block_ts: 0,
+            positions: vec![UniformPosition {
+                strategy_id: strategy.to_string(),
+                token_id: format!("token-{symbol}"),
+                token_symbol: symbol.to_string(),
+                token_decimals: 18,
+                total_shares: format!("{whole}{E18}"),
+                exchange_rate: String::new(),
+            }],
+            positions_incomplete: false,
+        }
+    }
+
+    fn page(operators: Vec<UniformOperator>) -> UniformPage {
+        UniformPage {
+            operators,
+            page_meta: PageMeta {
+                first: 0,
+                skip: 0,
+                next_cursor: None,
+                block: None,
+            },
+        }
+    }
+
+    fn fixtures() -> (UniformPage, UniformPage) {
+        let from = page(vec![
+            operator("0xa", "s-eth", "WETH", 1),
+            operator("0xb", "s-eigen", "EIGEN", 100),
+        ]);
+        let to = page(vec![
+            operator("0xa", "s-eth", "WETH", 2),
+            operator("0xb", "s-eigen", "EIGEN", 300),
+        ]);
+        (from, to)
+    }
+
+    fn ids(movers: &[Mover]) -> Vec<&str> {
+        movers.iter().map(|m| m.operator_id.as_str()).collect()
+    }
+
+    #[test]
+    fn movers_across_tokens_rank_by_usd() {
+        let (from, to) = fixtures();
+        let prices = PriceBook::new(BTreeMap::from([
+            ("WETH".to_string(), 2000.0),
+            ("EIGEN".to_string(), 1.0),
+        ]));
+        let diff = diff_pages(&from, &to, &prices, 10, 0);
+        assert_eq!(ids(&diff.movers), ["0xa", "0xb"]);
+        let usd = diff.movers[0].tvl_usd.as_ref().unwrap();
+        assert_eq!(usd.delta, 2000.0);
+    }
+
+    #[test]
+    fn movers_across_tokens_need_prices() {
+        let (from, to) = fixtures();
+        let diff = diff_pages(&from, &to, &PriceBook::default(), 10, 0);
+        assert!(diff.movers.is_empty());
+        assert_eq!(diff.changed.len(), 2);
+
+        let only_eth = PriceBook::new(BTreeMap::from([("WETH".to_string(), 2000.0)]));
+        let diff = diff_pages(&from, &to, &only_eth, 10, 0);
+        assert_eq!(ids(&diff.movers), ["0xa"]);
+    }
+
+    #[test]
+    fn movers_within_a_token_rank_by_amount() {
+        let (from, to) = fixtures();
+        let by_token = diff_by_token(&from, &to, 10, 0);
+        assert_eq!(ids(&by_token["token-EIGEN"].movers), ["0xb"]);
+        assert_eq!(
+            by_token["token-EIGEN"].movers[0].tvl.delta_atomic,
+            format!("200{E18}")
+        );
+        assert!(by_token["token-WETH"].movers[0].tvl_usd.is_none());
+    }
+}
@@ -1,40 +1,75 @@
+use crate::api::subgraph::client::SubgraphClient;
 use crate::api::subgraph::errors::InfraError;
-use crate::api::subgraph::queries::OPERATORS_SNAPSHOT;
-use crate::metrics::subgraph_observe;
-use crate::models::operators_snapshot::{OperatorsSnapshotData, OperatorsSnapshotVars};
-use crate::models::subgraph::{GraphQLRequest, GraphQLResponse};
-use reqwest::{Client, Url};
-use std::time::Instant;
+use crate::api::subgraph::queries::{OPERATORS_SNAPSHOT, OPERATORS_SNAPSHOT_CURSOR};
+use crate::models::operators_snapshot::{
+    BlockHeight, OperatorsSnapshotCursorVars, OperatorsSnapshotData, OperatorsSnapshotVars,
+};
+use crate::services::operators::operators_cursor::next_cursor_for;
+use crate::services::operators::operators_filter::OperatorsSnapshotFetcher;
+use crate::services::operators::operators_strategies::complete_operator_strategies;
+use futures::FutureExt;
+use futures::future::BoxFuture;
 
 pub async fn operators_snapshot(
-    client: Client,
-    endpoint: Url,
+    client: &SubgraphClient,
     vars: OperatorsSnapshotVars,
 ) -> Result<OperatorsSnapshotData, InfraError> {
-    let start = Instant::now();
-    let res = async {
-        let body = GraphQLRequest {
-            query: OPERATORS_SNAPSHOT,
-            variables: Some(vars),
+    let mut data: OperatorsSnapshotData = client.query(OPERATORS_SNAPSHOT, &vars).await?;
+    complete_operator_strategies(client, &mut data).await;
+    Ok(data)
+}
+
+pub async fn operators_snapshot_after(
+    client: &SubgraphClient,
+    vars: OperatorsSnapshotCursorVars,
+) -> Result<OperatorsSnapshotData, InfraError> {
+    let mut data: OperatorsSnapshotData = client.query(OPERATORS_SNAPSHOT_CURSOR, &vars).await?;
+    complete_operator_strategies(client, &mut data).await;
+    data.next_cursor = next_cursor_for(
+        data.operators.len(),
+        data.operators.last().map(|o| o.id.as_str()),
+        vars.first,
+    );
+    Ok(data)
+}
+
+/// Every operator, paged by id. Pages after the first are pinned to the block
+/// the first was served at so the result is one consistent state.
+pub async fn operators_snapshot_all(
+    client: &SubgraphClient,
+    page_size: i32,
+) -> Result<OperatorsSnapshotData, InfraError> {
+    let mut all = OperatorsSnapshotData {
+        operators: Vec::new(),
+        next_cursor: None,
+        meta: None,
+    };
+    let mut cursor = String::new();
+    loop {
+        let vars = OperatorsSnapshotCursorVars {
+            first: page_size,
+            cursor: cursor.clone(),
+            has_slashing: 0,
+            block: all.block_number().map(|number| BlockHeight { number }),
         };
-        let resp = client
-            .post(endpoint.clone())
-            .json(&body)
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<GraphQLResponse<OperatorsSnapshotData>>()
-            .await?;
-        if let Some(errs) = resp.errors {
-            return Err(InfraError::GraphQL(errs));
+        let page = operators_snapshot_after(client, vars).await?;
+        if all.meta.is_none() {
+            all.meta = page.meta;
+        }
+        let fetched = page.operators.len() as i32;
+        all.operators.extend(page.operators);
+        match all.operators.last() {
+            Some(last) if fetched >= page_size => cursor = last.id.clone(),
+            _ => return Ok(all),
         }
-        let data = resp.data.ok_or(InfraError::EmptyData)?;
-        Ok(data)
     }
-    .await;
-    match &res {
-        Ok(_) => subgraph_observe("ok", start.elapsed()),
-        Err(_) => subgraph_observe("error", start.elapsed()),
+}
+
+impl OperatorsSnapshotFetcher for SubgraphClient {
+    fn fetch(
+        &self,
+        vars: OperatorsSnapshotVars,
+    ) -> BoxFuture<'_, Result<OperatorsSnapshotData, anyhow::Error>> {
+        async move { Ok(operators_snapshot(self, vars).await?) }.boxed()
     }
-    res
 }
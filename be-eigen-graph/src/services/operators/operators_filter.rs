@@ -69,6 +69,7 @@ pub async fn list_operator_risk<F: OperatorsSnapshotFetcher>(
             SortDir::Desc => Desc,
         },
         has_slashing: params.has_slashing,
+        block: None,
     };
     let data = fetcher.fetch(vars).await?;
 
@@ -109,7 +110,7 @@ pub async fn list_operator_risk<F: OperatorsSnapshotFetcher>(
             StrategyCount => a.strategy_count.cmp(&b.strategy_count),
             SlashingCount => a.slashing_count.cmp(&b.slashing_count),
             LastSlashAt => a.last_slash_at.cmp(&b.last_slash_at),
-            LastUpdateBlockTs => std::cmp::Ordering::Equal,
+            LastUpdateBlockTs => a.last_update_block_ts.cmp(&b.last_update_block_ts),
         };
         match params.order.direction {
             SortDir::Asc => ord,
@@ -131,11 +132,11 @@ fn tvl_atomic_for_token(row: &OperatorRiskRow, token: &TokenId) -> Option<BigUin
     let mut sum = BigUint::zero();
     let mut any = false;
     for b in &row.tvl_by_token {
-        if &b.token.id == token {
-            if let Some(v) = parse_biguint(&b.amount_atomic.0) {
-                sum += v;
-                any = true;
-            }
+        if &b.token.id == token
+            && let Some(v) = parse_biguint(&b.amount_atomic.0)
+        {
+            sum += v;
+            any = true;
         }
     }
     if any { Some(sum) } else { None }
@@ -0,0 +1,117 @@
+use crate::errors::ApiError;
+use crate::models::operator_history::{
+    OperatorHistoryPoint, OperatorHistoryRow, StrategyHistoryPoint, StrategyHistoryRow,
+};
+use crate::models::operators_aggr::AggregatorParams;
+use crate::models::operators_snapshot::{AsOf, BlockHeight, OperatorsSnapshotData};
+use crate::repositories::operator_history::{
+    ensure_history_partitions, insert_operator_history, select_block_at, select_operator_history,
+    select_strategy_history,
+};
+use crate::services::operators::operators_aggr::from_subgraph_adapt::from_subgraph_adapt;
+use crate::services::operators::operators_aggr::operators_aggregator::aggregate;
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+/// Appends every operator and position of `page` to the history tables as
+/// sampled at `captured_at` by ingest run `run_id`. Re-appending the same
+/// sample is a no-op. As-of reads only see samples of runs that succeeded.
+pub async fn append_operators_history(
+    pool: &PgPool,
+    page: &OperatorsSnapshotData,
+    run_id: i64,
+    captured_at: i64,
+) -> Result<(), sqlx::Error> {
+    let (operators, strategies) = history_rows(page, captured_at);
+    if operators.is_empty() {
+        return Ok(());
+    }
+    ensure_history_partitions(pool, captured_at).await?;
+    insert_operator_history(
+        pool,
+        run_id,
+        captured_at,
+        page.block_number(),
+        &operators,
+        &strategies,
+    )
+    .await
+}
+
+/// Maps an as-of request to a block the subgraph can be queried at. A
+/// timestamp resolves to the block seen by the last succeeded ingest at or
+/// before it.
+pub async fn resolve_as_of_block(pool: &PgPool, as_of: AsOf) -> Result<BlockHeight, ApiError> {
+    match as_of {
+        AsOf::Block(number) => Ok(BlockHeight { number }),
+        AsOf::Timestamp(ts) => select_block_at(pool, ts)
+            .await?
+            .map(|number| BlockHeight { number })
+            .ok_or_else(|| ApiError::NotFound(format!("no ingested block at or before {ts}"))),
+    }
+}
+
+pub async fn operator_history_db(
+    pool: &PgPool,
+    operator_id: &str,
+    from: i64,
+    to: i64,
+    interval_secs: i64,
+) -> Result<(Vec<OperatorHistoryPoint>, Vec<StrategyHistoryPoint>), sqlx::Error> {
+    let points = select_operator_history(pool, operator_id, from, to, interval_secs).await?;
+    let strategies = select_strategy_history(pool, operator_id, from, to, interval_secs).await?;
+    Ok((points, strategies))
+}
+
+fn history_rows(
+    page: &OperatorsSnapshotData,
+    now_ts: i64,
+) -> (Vec<OperatorHistoryRow>, Vec<StrategyHistoryRow>) {
+    let uniform = from_subgraph_adapt(page, page.operators.len() as i32, 0);
+    let aggr = aggregate(&uniform, &AggregatorParams::default(), now_ts);
+
+    let mut operators = Vec::with_capacity(aggr.len());
+    let mut strategies = Vec::new();
+    for a in &aggr {
+        let op = uniform
+            .operators
+            .iter()
+            .find(|o| o.operator_id == a.operator_id);
+        operators.push(OperatorHistoryRow {
+            operator_id: a.operator_id.clone(),
+            avs_count: a.avs_count,
+            strategy_count: a.strategy_count,
+            slashing_count: a.slashing_count,
+            tvl_total_atomic: a.tvl_total_atomic.clone(),
+            hhi: a.hhi_strategy,
+            last_update_block_ts: a.last_update_block_ts,
+            positions_incomplete: op.is_some_and(|o| o.positions_incomplete),
+        });
+
+        let Some(op) = op else {
+            continue;
+        };
+        let tvl_by_strategy: HashMap<&str, &str> = a
+            .strategy_breakdown
+            .iter()
+            .map(|s| (s.strategy_id.as_str(), s.tvl_atomic.as_str()))
+            .collect();
+        strategies.extend(op.positions.iter().map(|p| {
+            StrategyHistoryRow {
+                operator_id: op.operator_id.clone(),
+                strategy_id: p.strategy_id.clone(),
+                token_id: p.token_id.clone(),
+                token_symbol: p.token_symbol.clone(),
+                token_decimals: p.token_decimals,
+                total_shares: p.total_shares.clone(),
+                exchange_rate: p.exchange_rate.clone(),
+                tvl_atomic: tvl_by_strategy
+                    .get(p.strategy_id.as_str())
+                    .unwrap_or(&"0")
+                    .to_string(),
+            }
+        }));
+    }
+
+    (operators, strategies)
+}
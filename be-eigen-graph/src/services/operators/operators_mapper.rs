@@ -4,8 +4,10 @@ use crate::models::operators_snapshot::{OperatorDto, OperatorsSnapshotData};
 use crate::models::strategy::OperatorStrategyPosition;
 use crate::models::time::BlockTimestamp;
 use crate::models::token::{AtomicAmount, TokenRef, TvlByToken};
+use crate::services::concentration::concentration_metrics::concentration_of;
+use crate::services::valuation::position_value::{TokenAmount, position_value};
 use num_bigint::BigUint;
-use num_traits::{One, Zero};
+use num_traits::Zero;
 use std::collections::HashMap;
 
 pub fn map_operators_snapshot(data: &OperatorsSnapshotData) -> Vec<OperatorRiskRow> {
@@ -40,7 +42,7 @@ fn map_operator(o: &OperatorDto) -> OperatorRiskRow {
         })
         .collect();
 
-    let hhi = compute_hhi_over_shares(&positions);
+    let hhi = compute_hhi(&positions);
 
     let tvl_by_token = compute_tvl_by_token(&positions);
 
@@ -50,6 +52,9 @@ fn map_operator(o: &OperatorDto) -> OperatorRiskRow {
         strategy_count: o.strategy_count as u32,
         slashing_count: o.slashing_count as u32,
         last_slash_at,
+        last_update_block_ts: BlockTimestamp(
+            o.last_update_block_timestamp.parse::<i64>().unwrap_or(0),
+        ),
         hhi,
         tvl_by_token,
         positions,
@@ -57,91 +62,46 @@ fn map_operator(o: &OperatorDto) -> OperatorRiskRow {
 }
 
 fn compute_tvl_by_token(positions: &[OperatorStrategyPosition]) -> Vec<TvlByToken> {
-    let mut acc: HashMap<String, (TokenRef, BigUint, u32)> = HashMap::new();
+    let mut acc: HashMap<String, (TokenRef, TokenAmount)> = HashMap::new();
 
     for p in positions {
-        let Some(shares) = parse_biguint(&p.total_shares_atomic.0) else {
-            continue;
-        };
-        let Some(rate) = parse_biguint(&p.exchange_rate_atomic.0) else {
-            continue;
-        };
-
-        if shares.is_zero() || rate.is_zero() {
+        let amount = position_value(
+            &p.total_shares_atomic.0,
+            &p.exchange_rate_atomic.0,
+            p.token.decimals as i32,
+        );
+        if amount.atomic.is_zero() {
             continue;
         }
 
-        let decimals = p.token.decimals as u32;
-        let scale = pow10(decimals);
-        let num = &shares * &rate;
-        let amount = &num / &scale;
-
         let entry = acc
             .entry(p.token.id.0.clone())
-            .or_insert_with(|| (p.token.clone(), BigUint::zero(), decimals));
-        entry.1 += amount;
+            .or_insert_with(|| (p.token.clone(), TokenAmount::zero(amount.decimals)));
+        entry.1.atomic += amount.atomic;
     }
 
     let mut out = Vec::with_capacity(acc.len());
-    for (_k, (token, sum_atomic, _dec)) in acc {
+    for (_k, (token, sum)) in acc {
         out.push(TvlByToken {
             token,
-            amount_atomic: AtomicAmount(sum_atomic.to_str_radix(10)),
+            amount_atomic: AtomicAmount(sum.atomic_string()),
+            amount_decimal: sum.decimal_string(),
         });
     }
     out
 }
 
-fn compute_hhi_over_shares(positions: &[OperatorStrategyPosition]) -> f64 {
-    let mut shares: Vec<BigUint> = Vec::with_capacity(positions.len());
-    for p in positions {
-        if let Some(s) = parse_biguint(&p.total_shares_atomic.0) {
-            if !s.is_zero() {
-                shares.push(s);
-            }
-        }
-    }
-    if shares.is_empty() {
-        return 0.0;
-    }
-    let sum: BigUint = shares
+fn compute_hhi(positions: &[OperatorStrategyPosition]) -> f64 {
+    let values: Vec<BigUint> = positions
         .iter()
-        .cloned()
-        .fold(BigUint::zero(), |acc, x| acc + x);
-    if sum.is_zero() {
-        return 0.0;
-    }
-
-    let sum_f = biguint_to_f64(&sum);
-    if sum_f == 0.0 || !sum_f.is_finite() {
-        return 0.0;
-    }
-
-    let mut hhi = 0.0_f64;
-    for s in shares {
-        let wi = biguint_to_f64(&s) / sum_f;
-        let wi = wi.clamp(0.0, 1.0);
-        hhi += wi * wi;
-    }
-    hhi.clamp(0.0, 1.0)
-}
-
-fn parse_biguint(s: &str) -> Option<BigUint> {
-    if s.is_empty() {
-        return None;
-    }
-    BigUint::parse_bytes(s.as_bytes(), 10)
-}
-
-fn pow10(n: u32) -> BigUint {
-    if n == 0 {
-        return BigUint::one();
-    }
-    let ten = BigUint::from(10_u32);
-    (0..n).fold(BigUint::one(), |acc, _| acc * &ten)
-}
-
-fn biguint_to_f64(n: &BigUint) -> f64 {
-    let s = n.to_str_radix(10);
-    s.parse::<f64>().unwrap_or(f64::INFINITY)
+        .map(|p| {
+            position_value(
+                &p.total_shares_atomic.0,
+                &p.exchange_rate_atomic.0,
+                p.token.decimals as i32,
+            )
+            .normalized()
+        })
+        .collect();
+    concentration_of(&values).hhi
 }
@@ -1,4 +1,5 @@
-use crate::models::operators_snapshot::OperatorsSnapshotData;
+use crate::metrics::error_inc;
+use crate::models::operators_snapshot::{AsOf, OperatorsSnapshotData};
 use crate::repositories::operators::upsert_operators_snapshot_page;
 use sqlx::{Pool, Postgres};
 
@@ -8,3 +9,17 @@ pub async fn persist_operators_snapshot_db(
 ) -> Result<(), sqlx::Error> {
     upsert_operators_snapshot_page(pool, page).await
 }
+
+/// Mirrors a page served by a live read. Historical (`as_of`) reads must not
+/// overwrite the current snapshot, so they are never persisted.
+pub async fn persist_head_snapshot(
+    pool: &Pool<Postgres>,
+    as_of: Option<AsOf>,
+    page: &OperatorsSnapshotData,
+) {
+    if as_of.is_none()
+        && let Err(_e) = persist_operators_snapshot_db(pool, page).await
+    {
+        error_inc("persist_operators_snapshot_db");
+    }
+}
@@ -0,0 +1,95 @@
+use crate::api::subgraph::client::SubgraphClient;
+use crate::api::subgraph::errors::InfraError;
+use crate::api::subgraph::queries::OPERATOR_SLASHINGS;
+use crate::errors::ApiError;
+use crate::metrics::error_inc;
+use crate::models::operators_snapshot::{
+    OperatorSlashingDto, OperatorSlashingsData, OperatorSlashingsVars, OperatorsSnapshotData,
+};
+use crate::repositories::operators::{select_slashing_counts, upsert_operator_slashings};
+use sqlx::PgPool;
+
+pub const SLASHINGS_PAGE_SIZE: i32 = 1000;
+const MAX_SLASHING_PAGES: i32 = 20;
+
+/// Slashings read by `load_slashings_after`; `incomplete` is set when
+/// `MAX_SLASHING_PAGES` ran out before the oldest one.
+pub struct LoadedSlashings {
+    pub slashings: Vec<OperatorSlashingDto>,
+    pub incomplete: bool,
+}
+
+/// An operator's slashings older than `after`, newest first; all of them
+/// when `after` is `None`.
+pub async fn load_slashings_after(
+    client: &SubgraphClient,
+    operator_id: &str,
+    after: Option<&OperatorSlashingDto>,
+) -> Result<LoadedSlashings, InfraError> {
+    let (mut before_ts, mut after_id) = match after {
+        Some(s) => (s.block_timestamp.clone(), s.id.clone().unwrap_or_default()),
+        None => (i64::MAX.to_string(), String::new()),
+    };
+    let mut out = Vec::new();
+    for _ in 0..MAX_SLASHING_PAGES {
+        let vars = OperatorSlashingsVars {
+            id: operator_id.to_string(),
+            first: SLASHINGS_PAGE_SIZE,
+            before_ts: before_ts.clone(),
+            after_id: after_id.clone(),
+        };
+        let page: OperatorSlashingsData = client.query(OPERATOR_SLASHINGS, &vars).await?;
+        let slashings = page.operator.map(|o| o.slashings).unwrap_or_default();
+        let fetched = slashings.len() as i32;
+        if let Some(last) = slashings.last() {
+            before_ts = last.block_timestamp.clone();
+            after_id = last.id.clone().unwrap_or_default();
+        }
+        out.extend(slashings);
+        if fetched < SLASHINGS_PAGE_SIZE {
+            return Ok(LoadedSlashings {
+                slashings: out,
+                incomplete: false,
+            });
+        }
+    }
+    Ok(LoadedSlashings {
+        slashings: out,
+        incomplete: true,
+    })
+}
+
+/// The snapshot pages only carry each operator's latest slashing. Operators
+/// whose `slashingCount` is ahead of the mirror get their full list fetched
+/// and stored, so the DB detail serves the whole history. A list cut short by
+/// the page cap is stored as far as it goes and retried on the next run,
+/// since the stored count stays behind.
+pub async fn sync_operator_slashings(
+    pool: &PgPool,
+    client: &SubgraphClient,
+    page: &OperatorsSnapshotData,
+) -> Result<(), ApiError> {
+    let ids: Vec<String> = page
+        .operators
+        .iter()
+        .filter(|o| o.slashing_count > 0)
+        .map(|o| o.id.clone())
+        .collect();
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let stored = select_slashing_counts(pool, &ids).await?;
+
+    for op in page.operators.iter().filter(|o| o.slashing_count > 0) {
+        let have = stored.get(&op.id).copied().unwrap_or(0);
+        if have >= i64::from(op.slashing_count) {
+            continue;
+        }
+        let loaded = load_slashings_after(client, &op.id, None).await?;
+        if loaded.incomplete {
+            error_inc("ingest_slashings_capped");
+        }
+        upsert_operator_slashings(pool, &op.id, &loaded.slashings).await?;
+    }
+    Ok(())
+}
@@ -1,56 +1,153 @@
-use crate::api::subgraph::queries::OPERATORS_SNAPSHOT;
-use crate::caching::redis::{get_json, key_snapshot, set_json};
-use crate::metrics::subgraph_observe;
+use crate::api::subgraph::client::SubgraphClient;
+use crate::api::subgraph::errors::InfraError;
+use crate::api::subgraph::queries::{OPERATORS_SNAPSHOT, OPERATORS_SNAPSHOT_CURSOR};
+use crate::caching::backend::{CacheEnvelope, CacheTier, CacheTtl, LayeredCache};
+use crate::caching::redis::{key_snapshot, key_snapshot_cursor};
+use crate::caching::single_flight::key_lock;
+use crate::metrics::cache_inc;
 use crate::models::cached::{Cached, DataSource};
-use crate::models::operators_snapshot::{OperatorsSnapshotData, OperatorsSnapshotVars};
-use crate::models::subgraph::{GraphQLRequest, GraphQLResponse};
-use redis::aio::ConnectionManager;
-use reqwest::{Client, Url};
-use std::time::Instant;
+use crate::models::operators_snapshot::{
+    OperatorsSnapshotCursorVars, OperatorsSnapshotData, OperatorsSnapshotVars,
+};
+use crate::services::operators::operators_cursor::next_cursor_for;
+use crate::services::operators::operators_strategies::complete_operator_strategies;
+use chrono::Utc;
+use serde::Serialize;
+use std::sync::Arc;
 
 pub async fn operators_snapshot_cached(
-    client: Client,
-    endpoint: Url,
+    client: &SubgraphClient,
     vars: OperatorsSnapshotVars,
-    redis: &Option<ConnectionManager>,
-    ttl_secs: u64,
-) -> Result<Cached<OperatorsSnapshotData>, anyhow::Error> {
-    if let Some(rm) = redis.as_ref() {
-        let key = key_snapshot(&vars);
-        let mut conn = rm.clone();
-        if let Some(hit) = get_json::<OperatorsSnapshotData>(&mut conn, &key).await {
-            return Ok(Cached {
-                source: DataSource::Redis,
-                data: hit,
-            });
+    cache: &Arc<LayeredCache>,
+    ttl: CacheTtl,
+) -> Result<Cached<OperatorsSnapshotData>, InfraError> {
+    let key = key_snapshot(&vars);
+    snapshot_cached(client, OPERATORS_SNAPSHOT, &vars, &key, cache, ttl).await
+}
+
+pub async fn operators_snapshot_after_cached(
+    client: &SubgraphClient,
+    vars: OperatorsSnapshotCursorVars,
+    cache: &Arc<LayeredCache>,
+    ttl: CacheTtl,
+) -> Result<Cached<OperatorsSnapshotData>, InfraError> {
+    let key = key_snapshot_cursor(&vars);
+    let mut cached =
+        snapshot_cached(client, OPERATORS_SNAPSHOT_CURSOR, &vars, &key, cache, ttl).await?;
+    cached.data.next_cursor = next_cursor_for(
+        cached.data.operators.len(),
+        cached.data.operators.last().map(|o| o.id.as_str()),
+        vars.first,
+    );
+    Ok(cached)
+}
+
+async fn snapshot_cached<V: Serialize>(
+    client: &SubgraphClient,
+    query: &'static str,
+    vars: &V,
+    key: &str,
+    cache: &Arc<LayeredCache>,
+    ttl: CacheTtl,
+) -> Result<Cached<OperatorsSnapshotData>, InfraError> {
+    if let Some((hit, tier)) = cache.get_json(key).await {
+        let cached = from_envelope(hit, tier, ttl);
+        if cached.stale {
+            spawn_refresh(client, query, vars, key, cache, ttl);
         }
+        return Ok(cached);
     }
 
-    let start = Instant::now();
-    let body = GraphQLRequest {
-        query: OPERATORS_SNAPSHOT,
-        variables: Some(vars),
-    };
-    let resp = client
-        .post(endpoint.clone())
-        .json(&body)
-        .send()
-        .await?
-        .error_for_status()?;
-    let resp = resp
-        .json::<GraphQLResponse<OperatorsSnapshotData>>()
-        .await?;
-    let data = resp.data.ok_or_else(|| anyhow::anyhow!("empty data"))?;
-    subgraph_observe("ok", start.elapsed());
-
-    if let Some(rm) = redis.as_ref() {
-        let key = key_snapshot(body.variables.as_ref().unwrap());
-        let mut conn = rm.clone();
-        let _ = set_json(&mut conn, &key, &data, ttl_secs).await;
+    let lock = key_lock(key);
+    let _inflight = lock.lock().await;
+
+    // another request may have filled the key while this one waited
+    if let Some((hit, tier)) = cache.get_json(key).await {
+        return Ok(from_envelope(hit, tier, ttl));
     }
 
+    let data = fetch_snapshot(client, query, vars).await?;
+    let cached_at = store(cache, key, &data, ttl).await;
+
     Ok(Cached {
         source: DataSource::Subgraph,
+        cached_at: Some(cached_at),
+        age_seconds: Some(0),
+        stale: false,
         data,
     })
 }
+
+async fn fetch_snapshot<V: Serialize + ?Sized>(
+    client: &SubgraphClient,
+    query: &str,
+    vars: &V,
+) -> Result<OperatorsSnapshotData, InfraError> {
+    let mut data: OperatorsSnapshotData = client.query(query, vars).await?;
+    complete_operator_strategies(client, &mut data).await;
+    Ok(data)
+}
+
+async fn store(
+    cache: &LayeredCache,
+    key: &str,
+    data: &OperatorsSnapshotData,
+    ttl: CacheTtl,
+) -> i64 {
+    let envelope = CacheEnvelope {
+        cached_at: Utc::now().timestamp(),
+        data,
+    };
+    let operator_ids: Vec<String> = data.operators.iter().map(|o| o.id.clone()).collect();
+    cache
+        .set_json_for_operators(key, &envelope, ttl.expire_secs(), &operator_ids)
+        .await;
+    envelope.cached_at
+}
+
+fn from_envelope(
+    hit: CacheEnvelope<OperatorsSnapshotData>,
+    tier: CacheTier,
+    ttl: CacheTtl,
+) -> Cached<OperatorsSnapshotData> {
+    let age = (Utc::now().timestamp() - hit.cached_at).max(0);
+    Cached {
+        source: tier.source(),
+        cached_at: Some(hit.cached_at),
+        age_seconds: Some(age),
+        stale: age as u64 >= ttl.fresh_secs,
+        data: hit.data,
+    }
+}
+
+/// Refreshes a stale key in the background unless a fetch for it is already
+/// in flight.
+fn spawn_refresh<V: Serialize>(
+    client: &SubgraphClient,
+    query: &'static str,
+    vars: &V,
+    key: &str,
+    cache: &Arc<LayeredCache>,
+    ttl: CacheTtl,
+) {
+    let Ok(inflight) = key_lock(key).try_lock_owned() else {
+        return;
+    };
+    let Ok(vars) = serde_json::to_value(vars) else {
+        return;
+    };
+    let client = client.clone();
+    let key = key.to_string();
+    let cache = cache.clone();
+
+    tokio::spawn(async move {
+        let _inflight = inflight;
+        match fetch_snapshot(&client, query, &vars).await {
+            Ok(data) => {
+                store(&cache, &key, &data, ttl).await;
+                cache_inc("refresh", "ok");
+            }
+            Err(_) => cache_inc("refresh", "err"),
+        }
+    });
+}
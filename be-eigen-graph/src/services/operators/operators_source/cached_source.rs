@@ -0,0 +1,38 @@
+use crate::api::subgraph::client::SubgraphClient;
+use crate::caching::backend::{CacheTtl, LayeredCache};
+use crate::errors::ApiError;
+use crate::models::cached::Cached;
+use crate::models::operators_aggr::UniformPage;
+use crate::services::operators::operators_aggregates_cached::uniform_page_from_subgraph_cached;
+use crate::services::operators::operators_source::subgraph_source::request_block;
+use crate::services::operators::operators_source::{PageRequest, UniformPageSource};
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+
+pub struct CachedSubgraphSource<'a> {
+    pub client: &'a SubgraphClient,
+    pub pool: &'a Pool<Postgres>,
+    pub cache: &'a Arc<LayeredCache>,
+    pub ttl: CacheTtl,
+}
+
+impl UniformPageSource for CachedSubgraphSource<'_> {
+    fn load(&self, req: PageRequest) -> BoxFuture<'_, Result<Cached<UniformPage>, ApiError>> {
+        async move {
+            let block = request_block(self.pool, &req).await?;
+            Ok(uniform_page_from_subgraph_cached(
+                self.client,
+                req.first,
+                req.skip,
+                req.cursor,
+                block,
+                self.cache,
+                self.ttl,
+            )
+            .await?)
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,31 @@
+use crate::errors::ApiError;
+use crate::models::cached::{Cached, DataSource};
+use crate::models::operators_aggr::UniformPage;
+use crate::services::operators::operators_aggr::from_db_adapt::{
+    from_db_adapt, from_db_adapt_after, from_db_history_adapt,
+};
+use crate::services::operators::operators_source::{PageRequest, UniformPageSource};
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use sqlx::{Pool, Postgres};
+
+pub struct DbSource<'a> {
+    pub pool: &'a Pool<Postgres>,
+}
+
+impl UniformPageSource for DbSource<'_> {
+    fn load(&self, req: PageRequest) -> BoxFuture<'_, Result<Cached<UniformPage>, ApiError>> {
+        async move {
+            let page = match (req.as_of, &req.cursor) {
+                (Some(as_of), cursor) => {
+                    from_db_history_adapt(self.pool, req.first, req.skip, cursor.as_deref(), as_of)
+                        .await?
+                }
+                (None, Some(c)) => from_db_adapt_after(self.pool, req.first, c).await?,
+                (None, None) => from_db_adapt(self.pool, req.first, req.skip).await?,
+            };
+            Ok(Cached::fresh(DataSource::Db, page))
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,65 @@
+use crate::errors::ApiError;
+use crate::models::cached::{Cached, DataSource};
+use crate::models::operators_aggr::UniformPage;
+use crate::models::operators_snapshot::OperatorsSnapshotData;
+use crate::services::operators::operators_aggr::from_subgraph_adapt::from_subgraph_adapt;
+use crate::services::operators::operators_cursor::next_cursor_for;
+use crate::services::operators::operators_source::{PageRequest, UniformPageSource};
+use anyhow::Context;
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use std::path::Path;
+
+/// Serves pages from a captured snapshot response such as
+/// `docs/responses-examples/operators-snapshot.json`.
+pub struct FixtureSource<'a> {
+    pub path: &'a Path,
+}
+
+impl UniformPageSource for FixtureSource<'_> {
+    fn load(&self, req: PageRequest) -> BoxFuture<'_, Result<Cached<UniformPage>, ApiError>> {
+        async move {
+            if req.as_of.is_some() {
+                return Err(ApiError::BadRequest(
+                    "the fixture source has no history for asOf queries".to_string(),
+                ));
+            }
+            let raw = tokio::fs::read(self.path)
+                .await
+                .with_context(|| format!("read fixture {}", self.path.display()))?;
+            let mut data: OperatorsSnapshotData =
+                serde_json::from_slice(&raw).context("decode fixture")?;
+
+            let first = req.first.max(0) as usize;
+            data.operators = match &req.cursor {
+                Some(cursor) => {
+                    data.operators.sort_by(|a, b| a.id.cmp(&b.id));
+                    data.operators
+                        .into_iter()
+                        .filter(|o| o.id.as_str() > cursor.as_str())
+                        .take(first)
+                        .collect()
+                }
+                None => data
+                    .operators
+                    .into_iter()
+                    .skip(req.skip.max(0) as usize)
+                    .take(first)
+                    .collect(),
+            };
+            data.next_cursor = req.cursor.as_ref().and_then(|_| {
+                next_cursor_for(
+                    data.operators.len(),
+                    data.operators.last().map(|o| o.id.as_str()),
+                    req.first,
+                )
+            });
+
+            Ok(Cached::fresh(
+                DataSource::Fixture,
+                from_subgraph_adapt(&data, req.first, req.skip),
+            ))
+        }
+        .boxed()
+    }
+}
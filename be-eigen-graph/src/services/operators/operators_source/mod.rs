@@ -0,0 +1,91 @@
+pub mod cached_source;
+pub mod db_source;
+pub mod fixture_source;
+pub mod subgraph_source;
+
+use crate::errors::ApiError;
+use crate::models::cached::Cached;
+use crate::models::operators_aggr::UniformPage;
+use crate::models::operators_snapshot::AsOf;
+use crate::state::AppState;
+use cached_source::CachedSubgraphSource;
+use db_source::DbSource;
+use fixture_source::FixtureSource;
+use futures::future::BoxFuture;
+use std::str::FromStr;
+use subgraph_source::SubgraphSource;
+
+#[derive(Clone, Debug)]
+pub struct PageRequest {
+    pub first: i32,
+    pub skip: i32,
+    pub cursor: Option<String>,
+    pub as_of: Option<AsOf>,
+}
+
+/// Anything that can produce a `UniformPage` for the aggregation pipeline.
+pub trait UniformPageSource: Send + Sync {
+    fn load(&self, req: PageRequest) -> BoxFuture<'_, Result<Cached<UniformPage>, ApiError>>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PageSourceKind {
+    Live,
+    Cached,
+    Db,
+    Fixture,
+}
+
+impl PageSourceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PageSourceKind::Live => "live",
+            PageSourceKind::Cached => "cached",
+            PageSourceKind::Db => "db",
+            PageSourceKind::Fixture => "fixture",
+        }
+    }
+
+    pub fn parse_or(s: Option<&str>, default: Self) -> Result<Self, ApiError> {
+        s.map(str::parse).unwrap_or(Ok(default))
+    }
+}
+
+impl FromStr for PageSourceKind {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "live" => Ok(PageSourceKind::Live),
+            "cached" => Ok(PageSourceKind::Cached),
+            "db" => Ok(PageSourceKind::Db),
+            "fixture" => Ok(PageSourceKind::Fixture),
+            other => Err(ApiError::BadRequest(format!("unknown source: {other}"))),
+        }
+    }
+}
+
+pub fn page_source_for(
+    state: &AppState,
+    kind: PageSourceKind,
+) -> Result<Box<dyn UniformPageSource + '_>, ApiError> {
+    Ok(match kind {
+        PageSourceKind::Live => Box::new(SubgraphSource {
+            client: &state.subgraph_client,
+            pool: &state.db,
+        }),
+        PageSourceKind::Cached => Box::new(CachedSubgraphSource {
+            client: &state.subgraph_client,
+            pool: &state.db,
+            cache: &state.cache,
+            ttl: state.cache_ttl(),
+        }),
+        PageSourceKind::Db => Box::new(DbSource { pool: &state.db }),
+        PageSourceKind::Fixture => {
+            let path = state.fixture_path.as_deref().ok_or_else(|| {
+                ApiError::BadRequest("fixture source is not configured".to_string())
+            })?;
+            Box::new(FixtureSource { path })
+        }
+    })
+}
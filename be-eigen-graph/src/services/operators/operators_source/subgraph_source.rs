@@ -0,0 +1,68 @@
+use crate::api::subgraph::client::SubgraphClient;
+use crate::errors::ApiError;
+use crate::models::cached::{Cached, DataSource};
+use crate::models::operators_aggr::UniformPage;
+use crate::models::operators_snapshot::{
+    BlockHeight, OperatorOrderBy, OperatorsSnapshotCursorVars, OperatorsSnapshotVars,
+    OrderDirection,
+};
+use crate::services::operators::operators_aggr::from_subgraph_adapt::from_subgraph_adapt;
+use crate::services::operators::operators_fetcher::{operators_snapshot, operators_snapshot_after};
+use crate::services::operators::operators_history::resolve_as_of_block;
+use crate::services::operators::operators_source::{PageRequest, UniformPageSource};
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use sqlx::{Pool, Postgres};
+
+pub struct SubgraphSource<'a> {
+    pub client: &'a SubgraphClient,
+    pub pool: &'a Pool<Postgres>,
+}
+
+/// The `block` argument for a request, resolving timestamps through the
+/// retained history.
+pub async fn request_block(
+    pool: &Pool<Postgres>,
+    req: &PageRequest,
+) -> Result<Option<BlockHeight>, ApiError> {
+    match req.as_of {
+        Some(as_of) => Ok(Some(resolve_as_of_block(pool, as_of).await?)),
+        None => Ok(None),
+    }
+}
+
+impl UniformPageSource for SubgraphSource<'_> {
+    fn load(&self, req: PageRequest) -> BoxFuture<'_, Result<Cached<UniformPage>, ApiError>> {
+        async move {
+            let block = request_block(self.pool, &req).await?;
+            let page = match req.cursor {
+                Some(cursor) => {
+                    let vars = OperatorsSnapshotCursorVars {
+                        first: req.first,
+                        cursor,
+                        has_slashing: 0,
+                        block,
+                    };
+                    operators_snapshot_after(self.client, vars).await?
+                }
+                None => {
+                    let vars = OperatorsSnapshotVars {
+                        first: req.first,
+                        skip: req.skip,
+                        has_slashing: 0,
+                        order_by: OperatorOrderBy::LastUpdateBlockTimestamp,
+                        order_direction: OrderDirection::Desc,
+                        block,
+                    };
+                    operators_snapshot(self.client, vars).await?
+                }
+            };
+
+            Ok(Cached::fresh(
+                DataSource::Subgraph,
+                from_subgraph_adapt(&page, req.first, req.skip),
+            ))
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,84 @@
+use crate::api::subgraph::client::SubgraphClient;
+use crate::api::subgraph::errors::InfraError;
+use crate::api::subgraph::queries::OPERATOR_STRATEGIES;
+use crate::models::operators_snapshot::{
+    BlockHeight, OperatorDto, OperatorStrategiesData, OperatorStrategiesVars,
+    OperatorStrategyPageLinkDto, OperatorsSnapshotData,
+};
+
+pub const STRATEGIES_PAGE_SIZE: i32 = 100;
+const MAX_STRATEGY_PAGES: i32 = 50;
+
+pub async fn complete_operator_strategies(
+    client: &SubgraphClient,
+    data: &mut OperatorsSnapshotData,
+) {
+    // follow-up pages read the same block as the snapshot they complete
+    let block = data.block_number().map(|number| BlockHeight { number });
+    for op in data.operators.iter_mut() {
+        complete_strategies_at(client, op, block).await;
+    }
+}
+
+pub async fn complete_strategies_for(client: &SubgraphClient, op: &mut OperatorDto) {
+    complete_strategies_at(client, op, None).await;
+}
+
+async fn complete_strategies_at(
+    client: &SubgraphClient,
+    op: &mut OperatorDto,
+    block: Option<BlockHeight>,
+) {
+    if (op.strategies.len() as i32) < STRATEGIES_PAGE_SIZE {
+        return;
+    }
+    if let Err(_e) = load_remaining_strategies(client, op, block).await {
+        op.strategies_incomplete = true;
+    }
+}
+
+/// The embedded first page is ordered by update time, which shifts under
+/// concurrent updates, so the rest is walked from the start by link id and
+/// merged into what is already there.
+async fn load_remaining_strategies(
+    client: &SubgraphClient,
+    op: &mut OperatorDto,
+    block: Option<BlockHeight>,
+) -> Result<(), InfraError> {
+    let mut cursor = String::new();
+    for _ in 0..MAX_STRATEGY_PAGES {
+        let vars = OperatorStrategiesVars {
+            id: op.id.clone(),
+            first: STRATEGIES_PAGE_SIZE,
+            cursor: cursor.clone(),
+            block,
+        };
+        let links = operator_strategies_page(client, vars).await?;
+        let fetched = links.len() as i32;
+        if let Some(last) = links.last() {
+            cursor = last.id.clone();
+        }
+        for page_link in links {
+            if !op
+                .strategies
+                .iter()
+                .any(|l| l.strategy.id == page_link.link.strategy.id)
+            {
+                op.strategies.push(page_link.link);
+            }
+        }
+        if fetched < STRATEGIES_PAGE_SIZE {
+            return Ok(());
+        }
+    }
+    op.strategies_incomplete = true;
+    Ok(())
+}
+
+async fn operator_strategies_page(
+    client: &SubgraphClient,
+    vars: OperatorStrategiesVars,
+) -> Result<Vec<OperatorStrategyPageLinkDto>, InfraError> {
+    let data: OperatorStrategiesData = client.query(OPERATOR_STRATEGIES, &vars).await?;
+    Ok(data.operator.map(|o| o.strategies).unwrap_or_default())
+}
@@ -0,0 +1,2 @@
+pub mod price_book;
+pub mod price_feed;
@@ -0,0 +1,32 @@
+use crate::services::valuation::position_value::TokenAmount;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default)]
+pub struct PriceBook {
+    usd_by_symbol: BTreeMap<String, f64>,
+}
+
+impl PriceBook {
+    pub fn new(usd_by_symbol: BTreeMap<String, f64>) -> Self {
+        Self {
+            usd_by_symbol: usd_by_symbol
+                .into_iter()
+                .map(|(k, v)| (k.to_lowercase(), v))
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.usd_by_symbol.is_empty()
+    }
+
+    pub fn usd_price(&self, symbol: &str) -> Option<f64> {
+        self.usd_by_symbol.get(&symbol.to_lowercase()).copied()
+    }
+
+    pub fn usd_value(&self, symbol: &str, amount: &TokenAmount) -> Option<f64> {
+        let price = self.usd_price(symbol)?;
+        let usd = amount.to_f64() * price;
+        usd.is_finite().then_some(usd)
+    }
+}
@@ -0,0 +1,64 @@
+use crate::metrics::error_inc;
+use crate::repositories::ticks::select_latest_prices;
+use crate::services::prices::price_book::PriceBook;
+use sqlx::{Pool, Postgres};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Reads the latest `be-stream` ticks and maps them onto token symbols.
+#[derive(Clone, Default)]
+pub struct PriceFeed {
+    pool: Option<Pool<Postgres>>,
+    products: Arc<BTreeMap<String, String>>,
+}
+
+impl PriceFeed {
+    pub fn new(pool: Option<Pool<Postgres>>, products: BTreeMap<String, String>) -> Self {
+        Self {
+            pool,
+            products: Arc::new(products),
+        }
+    }
+
+    /// Parses `SYMBOL=PRODUCT` pairs separated by commas, e.g. `WETH=ETH-USD`.
+    pub fn parse_products(spec: &str) -> BTreeMap<String, String> {
+        spec.split(',')
+            .filter_map(|pair| {
+                let (symbol, product) = pair.split_once('=')?;
+                let (symbol, product) = (symbol.trim(), product.trim());
+                (!symbol.is_empty() && !product.is_empty())
+                    .then(|| (symbol.to_string(), product.to_string()))
+            })
+            .collect()
+    }
+
+    pub async fn latest(&self) -> PriceBook {
+        let Some(pool) = &self.pool else {
+            return PriceBook::default();
+        };
+        if self.products.is_empty() {
+            return PriceBook::default();
+        }
+
+        let mut product_ids: Vec<&str> = self.products.values().map(String::as_str).collect();
+        product_ids.sort_unstable();
+        product_ids.dedup();
+
+        let prices = match select_latest_prices(pool, &product_ids).await {
+            Ok(prices) => prices,
+            Err(_e) => {
+                error_inc("price_feed");
+                return PriceBook::default();
+            }
+        };
+
+        PriceBook::new(
+            self.products
+                .iter()
+                .filter_map(|(symbol, product)| {
+                    prices.get(product).map(|price| (symbol.clone(), *price))
+                })
+                .collect(),
+        )
+    }
+}
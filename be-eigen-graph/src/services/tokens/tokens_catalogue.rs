@@ -0,0 +1,114 @@
+use crate::models::operators_aggr::{AggregatorParams, UniformPage};
+use crate::payloads::tokens::TokenView;
+use crate::services::concentration::concentration_metrics::concentration_of;
+use crate::services::operators::operators_aggr::operators_aggregator;
+use crate::services::operators::operators_aggr::operators_part::partition_by_token_id;
+use crate::services::valuation::position_value::TokenAmount;
+use num_bigint::BigUint;
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+pub fn build_token_catalogue(page: &UniformPage, now_ts: i64) -> Vec<TokenView> {
+    let params = AggregatorParams::default();
+
+    let mut out: Vec<(BigUint, TokenView)> = partition_by_token_id(page)
+        .into_iter()
+        .filter_map(|(token_id, token_page)| {
+            let first = token_page.operators.first()?.positions.first()?;
+            let symbol = first.token_symbol.clone();
+            let decimals = first.token_decimals;
+
+            let aggr = operators_aggregator::aggregate(&token_page, &params, now_ts);
+            let tvl = TokenAmount {
+                atomic: operators_aggregator::token_tvl_atomic(&aggr),
+                decimals: decimals.max(0) as u32,
+            };
+            let operator_count = aggr.iter().filter(|a| a.nonzero_strategy_count > 0).count();
+            let weights: Vec<BigUint> = aggr
+                .iter()
+                .map(|a| operators_aggregator::token_tvl_atomic(std::slice::from_ref(a)))
+                .collect();
+            let strategies: BTreeSet<String> = token_page
+                .operators
+                .iter()
+                .flat_map(|o| o.positions.iter().map(|p| p.strategy_id.clone()))
+                .collect();
+
+            Some((
+                tvl.normalized(),
+                TokenView {
+                    token_id,
+                    symbol,
+                    decimals,
+                    tvl_total_atomic: tvl.atomic_string(),
+                    tvl_total_decimal: tvl.decimal_string(),
+                    operator_count,
+                    concentration: concentration_of(&weights),
+                    strategies: strategies.into_iter().collect(),
+                },
+            ))
+        })
+        .collect();
+
+    out.sort_by(|(at, a), (bt, b)| match bt.cmp(at) {
+        Ordering::Equal => (&a.symbol, &a.token_id).cmp(&(&b.symbol, &b.token_id)),
+        o => o,
+    });
+
+    out.into_iter().map(|(_, t)| t).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::operators_aggr::{PageMeta, UniformOperator, UniformPosition};
+
+    fn position(strategy: &str, token: &str, decimals: i32, shares: &str) -> UniformPosition {
+        UniformPosition {
+            strategy_id: strategy.to_string(),
+            token_id: token.to_string(),
+            token_symbol: "USDC".to_string(),
+            token_decimals: decimals,
+            total_shares: shares.to_string(),
+            exchange_rate: String::new(),
+        }
+    }
+
+    #[test]
+    fn tokens_sharing_a_symbol_stay_apart() {
+        let page = UniformPage {
+            operators: vec![UniformOperator {
+                operator_id: "0xop".to_string(),
+                avs_count: 1,
+                strategy_count: 2,
+                slashing_count: 0,
+                last_slash_at: None,
+                last_update_block_ts: 0,
+                positions: vec![
+                    position("s-native", "0xnative", 6, "5000000"),
+                    position("s-bridged", "0xbridged", 18, "2000000000000000000"),
+                ],
+                positions_incomplete: false,
+            }],
+            page_meta: PageMeta {
+                first: 0,
+                skip: 0,
+                next_cursor: None,
+                block: None,
+            },
+        };
+
+        let catalogue = build_token_catalogue(&page, 0);
+        assert_eq!(catalogue.len(), 2);
+        let native = catalogue.iter().find(|t| t.token_id == "0xnative").unwrap();
+        assert_eq!(native.decimals, 6);
+        assert_eq!(native.tvl_total_decimal, "5");
+        assert_eq!(native.strategies, vec!["s-native".to_string()]);
+        let bridged = catalogue
+            .iter()
+            .find(|t| t.token_id == "0xbridged")
+            .unwrap();
+        assert_eq!(bridged.decimals, 18);
+        assert_eq!(bridged.tvl_total_decimal, "2");
+    }
+}
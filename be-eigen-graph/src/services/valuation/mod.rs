@@ -0,0 +1 @@
+pub mod position_value;
@@ -0,0 +1,167 @@
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive, Zero};
+
+/// Strategy exchange rates are fixed-point with this many decimals: one share
+/// is worth `exchangeRate / 10^18` underlying tokens.
+pub const EXCHANGE_RATE_DECIMALS: u32 = 18;
+
+/// Cross-token totals are summed after rescaling every amount to this many
+/// decimals, so a 6-decimal token is not undervalued against 18-decimal ones.
+pub const NORMALIZED_DECIMALS: u32 = 18;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenAmount {
+    pub atomic: BigUint,
+    pub decimals: u32,
+}
+
+impl TokenAmount {
+    pub fn zero(decimals: u32) -> Self {
+        Self {
+            atomic: BigUint::zero(),
+            decimals,
+        }
+    }
+
+    pub fn atomic_string(&self) -> String {
+        self.atomic.to_string()
+    }
+
+    pub fn decimal_string(&self) -> String {
+        format_units(&self.atomic, self.decimals)
+    }
+
+    pub fn normalized(&self) -> BigUint {
+        if self.decimals <= NORMALIZED_DECIMALS {
+            &self.atomic * pow10(NORMALIZED_DECIMALS - self.decimals)
+        } else {
+            &self.atomic / pow10(self.decimals - NORMALIZED_DECIMALS)
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let whole = self.atomic.to_f64().unwrap_or(0.0);
+        whole / 10f64.powi(self.decimals as i32)
+    }
+}
+
+/// Underlying token amount of a strategy position, in the token's atomic
+/// units. An empty or zero exchange rate is treated as 1:1 because the
+/// subgraph reports `0` for strategies that have not been rebased yet.
+pub fn position_value(total_shares: &str, exchange_rate: &str, decimals: i32) -> TokenAmount {
+    let decimals = decimals.max(0) as u32;
+    let Some(shares) = parse_units(total_shares, 0) else {
+        return TokenAmount::zero(decimals);
+    };
+    // the rate is already a fixed-point integer, e.g. "1000000000000000000"
+    let rate = parse_units(exchange_rate, 0)
+        .filter(|r| !r.is_zero())
+        .unwrap_or_else(|| pow10(EXCHANGE_RATE_DECIMALS));
+
+    TokenAmount {
+        atomic: shares * rate / pow10(EXCHANGE_RATE_DECIMALS),
+        decimals,
+    }
+}
+
+/// Parses an integer string, or a decimal string scaled up by `decimals`
+/// (extra fractional digits are truncated).
+pub fn parse_units(s: &str, decimals: u32) -> Option<BigUint> {
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+    let (whole, frac) = s.split_once('.').unwrap_or((s, ""));
+    let mut digits = String::with_capacity(whole.len() + decimals as usize);
+    digits.push_str(if whole.is_empty() { "0" } else { whole });
+    let frac: String = frac.chars().take(decimals as usize).collect();
+    digits.push_str(&frac);
+    for _ in frac.len()..decimals as usize {
+        digits.push('0');
+    }
+    BigUint::parse_bytes(digits.as_bytes(), 10)
+}
+
+/// Renders atomic units as a decimal string without trailing zeros.
+pub fn format_units(atomic: &BigUint, decimals: u32) -> String {
+    if decimals == 0 {
+        return atomic.to_string();
+    }
+    let digits = format!(
+        "{:0>width$}",
+        atomic.to_string(),
+        width = decimals as usize + 1
+    );
+    let (whole, frac) = digits.split_at(digits.len() - decimals as usize);
+    let frac = frac.trim_end_matches('0');
+    if frac.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{whole}.{frac}")
+    }
+}
+
+pub fn pow10(n: u32) -> BigUint {
+    let ten = BigUint::from(10_u32);
+    (0..n).fold(BigUint::one(), |acc, _| acc * &ten)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE_RATE: &str = "1000000000000000000";
+
+    #[test]
+    fn zero_rate_is_one_to_one() {
+        let v = position_value("1000", "0", 18);
+        assert_eq!(v.atomic, BigUint::from(1000_u32));
+        assert_eq!(
+            position_value("1000", "", 18).atomic,
+            BigUint::from(1000_u32)
+        );
+    }
+
+    #[test]
+    fn unit_rate_keeps_shares() {
+        let v = position_value("1000", ONE_RATE, 18);
+        assert_eq!(v.atomic, BigUint::from(1000_u32));
+        assert_eq!(v.decimals, 18);
+    }
+
+    #[test]
+    fn fractional_rate_scales_shares() {
+        // 1.05 underlying per share
+        let v = position_value("2000000000000000000", "1050000000000000000", 18);
+        assert_eq!(v.decimal_string(), "2.1");
+    }
+
+    #[test]
+    fn six_decimal_token() {
+        let v = position_value("2500000", ONE_RATE, 6);
+        assert_eq!(v.decimals, 6);
+        assert_eq!(v.decimal_string(), "2.5");
+        assert_eq!(v.normalized(), parse_units("2.5", 18).unwrap());
+        assert!((v.to_f64() - 2.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn unparsable_shares_are_zero() {
+        assert_eq!(position_value("abc", ONE_RATE, 18), TokenAmount::zero(18));
+    }
+
+    #[test]
+    fn parse_and_format_units_round_trip() {
+        assert_eq!(parse_units("1.5", 6), Some(BigUint::from(1_500_000_u32)));
+        assert_eq!(
+            parse_units("1.1234567", 6),
+            Some(BigUint::from(1_123_456_u32))
+        );
+        assert_eq!(parse_units(".5", 2), Some(BigUint::from(50_u32)));
+        assert_eq!(parse_units("", 2), None);
+        assert_eq!(format_units(&BigUint::from(1_500_000_u32), 6), "1.5");
+        assert_eq!(format_units(&BigUint::from(5_u32), 6), "0.000005");
+        assert_eq!(format_units(&BigUint::from(7_u32), 0), "7");
+        assert_eq!(format_units(&BigUint::from(3_000_000_u32), 6), "3");
+    }
+}
@@ -1,14 +1,32 @@
 use crate::api::subgraph::client::SubgraphClient;
-use crate::models::operators_snapshot::OperatorDto;
-use redis::aio::ConnectionManager;
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use crate::caching::backend::{CacheTtl, LayeredCache};
+use crate::services::alerts::webhook_sender::WebhookSender;
+use crate::services::live::live_hub::LiveHub;
+use crate::services::prices::price_feed::PriceFeed;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct AppState {
     pub subgraph_client: SubgraphClient,
-    pub operators_snapshot: Arc<Mutex<HashMap<String, OperatorDto>>>,
     pub db: sqlx::Pool<sqlx::Postgres>,
-    pub redis: Option<ConnectionManager>,
+    pub cache: Arc<LayeredCache>,
     pub redis_ttl_seconds: u64,
+    pub redis_stale_seconds: u64,
+    pub prices: PriceFeed,
+    pub fixture_path: Option<PathBuf>,
+    pub admin_token: Option<String>,
+    pub webhooks: WebhookSender,
+    pub alert_hhi_threshold: f64,
+    pub alert_recent_window_seconds: i64,
+    pub live: LiveHub,
+}
+
+impl AppState {
+    pub fn cache_ttl(&self) -> CacheTtl {
+        CacheTtl {
+            fresh_secs: self.redis_ttl_seconds,
+            stale_secs: self.redis_stale_seconds,
+        }
+    }
 }
@@ -6,7 +6,7 @@ export interface TableItem {
     lastSlashAt: number | null;
     lastUpdateBlockTs: number;
     tvlTotalAtomic: string;
-    hhiStrategy: number;
+    hhiStrategy: number | null;
     nonzeroStrategyCount: number;
 }
 
@@ -16,7 +16,7 @@ export interface BarItem {
 }
 
 export interface DonutSlice {
-    share: number;
+    share: number | null;
     strategyId: string;
     tvlAtomic: string;
 }